@@ -0,0 +1,60 @@
+use icu::locid::locale;
+use platformed_mf::{CompiledMessage, format_with_locale, params};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const TEMPLATE: &str = "{count, plural, one{# item} other{# items}} for {total, number}";
+
+// Every element after the first reuses a formatter built by an earlier one
+const MANY_ELEMENTS: &str = "{a, number} {b, number} {c, number} {a, number, integer} {b, number, percent} \
+    {c, number, percent} {a, number, currency/EUR} {b, number, currency/USD} \
+    {d, date, short} {d, date, short} {t, time} {t, time}";
+
+// Each level of nesting formats its case into the shared output buffer
+const NESTED: &str = "{a, select, x{{b, plural, one{# {c, select, y{{d, plural, other{# deep}}} other{}}} other{}}} other{}}";
+
+const ITERATIONS: u32 = 10_000;
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = start.elapsed();
+    println!("{name:<32} {:>10.2?} per call", elapsed / ITERATIONS);
+}
+
+fn main() {
+    let locale = locale!("en-US");
+
+    bench("format_with_locale", || {
+        let formatted = format_with_locale(TEMPLATE, params!("count" => 3, "total" => 1234), &locale);
+        black_box(formatted.unwrap());
+    });
+
+    let compiled = CompiledMessage::new(TEMPLATE, &locale).unwrap();
+    bench("CompiledMessage::format", || {
+        let formatted = compiled.format(params!("count" => 3, "total" => 1234));
+        black_box(formatted.unwrap());
+    });
+
+    bench("format_with_locale (12 elements)", || {
+        black_box(format_with_locale(
+            MANY_ELEMENTS,
+            params!("a" => 1234.5, "b" => 0.25, "c" => 42, "d" => "2024-03-15", "t" => "14:30"),
+            &locale,
+        ).unwrap());
+    });
+
+    let compiled = CompiledMessage::new(MANY_ELEMENTS, &locale).unwrap();
+    bench("CompiledMessage (12 elements)", || {
+        black_box(compiled.format(
+            params!("a" => 1234.5, "b" => 0.25, "c" => 42, "d" => "2024-03-15", "t" => "14:30"),
+        ).unwrap());
+    });
+
+    let compiled = CompiledMessage::new(NESTED, &locale).unwrap();
+    bench("CompiledMessage (nested cases)", || {
+        black_box(compiled.format(params!("a" => "x", "b" => 1, "c" => "y", "d" => 5)).unwrap());
+    });
+}
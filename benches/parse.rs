@@ -0,0 +1,57 @@
+use platformed_mf::{parse_message_borrowed, parse_message_complete};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+// Counts allocations so the owned and borrowed parsers can be compared directly
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// A catalog of short templates, like the messages of a translated UI
+fn catalog() -> Vec<String> {
+    (0..5_000)
+        .map(|i| match i % 4 {
+            0 => format!("Welcome back, {{name}}! Message {i}"),
+            1 => format!("{{count, plural, one{{# new item}} other{{# new items}}}} in folder {i}"),
+            2 => format!("{{gender, select, female{{She}} male{{He}} other{{They}}}} replied to thread {i}"),
+            _ => format!("Saved {{total, number}} files to disk {i}"),
+        })
+        .collect()
+}
+
+fn bench(name: &str, catalog: &[String], mut parse: impl FnMut(&str)) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for template in catalog {
+        parse(template);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!("{name:<24} {elapsed:>10.2?} {allocations:>8} allocations for {} templates", catalog.len());
+}
+
+fn main() {
+    let catalog = catalog();
+    bench("parse_message_complete", &catalog, |t| {
+        black_box(parse_message_complete(t).unwrap());
+    });
+    bench("parse_message_borrowed", &catalog, |t| {
+        black_box(parse_message_borrowed(t).unwrap());
+    });
+}
@@ -0,0 +1,91 @@
+use crate::formatter::{FormatOptions, FormatterCache, format_message_cached};
+use crate::parser::parse_message_complete;
+use crate::types::{Message, ParameterSource};
+use crate::MessageFormatError;
+use alloc::string::String;
+use icu::locid::Locale;
+
+// A template parsed once for a fixed locale. The ICU formatters it needs are
+// built on first use and reused by every subsequent `format` call.
+pub struct CompiledMessage {
+    message: Message,
+    cache: FormatterCache,
+}
+
+impl CompiledMessage {
+    pub fn new(template: &str, locale: &Locale) -> Result<Self, MessageFormatError> {
+        let message = parse_message_complete(template)?;
+        Ok(Self::from_message(message, locale))
+    }
+
+    // Falls back to each of `fallbacks` in order for data `locale` lacks
+    pub fn with_fallbacks(template: &str, locale: &Locale, fallbacks: &[Locale]) -> Result<Self, MessageFormatError> {
+        let message = parse_message_complete(template)?;
+        Ok(Self { message, cache: FormatterCache::with_fallbacks(locale, fallbacks) })
+    }
+
+    pub fn from_message(message: Message, locale: &Locale) -> Self {
+        Self {
+            message,
+            cache: FormatterCache::new(locale),
+        }
+    }
+
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    pub fn locale(&self) -> &Locale {
+        self.cache.locale()
+    }
+
+    pub fn format<P: ParameterSource>(&self, parameters: P) -> Result<String, MessageFormatError> {
+        self.format_with_options(parameters, &FormatOptions::default())
+    }
+
+    pub fn format_with_options<P: ParameterSource>(
+        &self,
+        parameters: P,
+        options: &FormatOptions,
+    ) -> Result<String, MessageFormatError> {
+        Ok(format_message_cached(&self.message, parameters, &self.cache, options)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params;
+    use icu::locid::locale;
+
+    #[test]
+    fn test_compiled_message_reuse() {
+        let compiled = CompiledMessage::new(
+            "{count, plural, one{# item} other{# items}} for {total, number}",
+            &locale!("de-DE"),
+        )
+        .unwrap();
+
+        assert_eq!(compiled.format(params!("count" => 1, "total" => 1234)).unwrap(), "1 item for 1.234");
+        assert_eq!(compiled.format(params!("count" => 5, "total" => 99)).unwrap(), "5 items for 99");
+    }
+
+    #[test]
+    fn test_compiled_message_fallbacks() {
+        let compiled = CompiledMessage::with_fallbacks("{price, number, currency}", &locale!("en-AQ"), &[locale!("en-US")]).unwrap();
+        assert_eq!(compiled.locale(), &locale!("en-AQ"));
+        assert_eq!(compiled.format(params!("price" => 5)).unwrap(), "$5.00");
+    }
+
+    #[test]
+    fn test_compiled_message_parse_error() {
+        let result = CompiledMessage::new("Hello {name", &locale!("en"));
+        assert!(matches!(result, Err(MessageFormatError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_compiled_message_format_error() {
+        let compiled = CompiledMessage::new("Hello {name}!", &locale!("en")).unwrap();
+        assert!(matches!(compiled.format(params!()), Err(MessageFormatError::FormatError(_))));
+    }
+}
@@ -1,50 +1,145 @@
 use crate::types::{
-    Message, MessageElement, ParameterValue, Parameters, PluralExpression, PluralSelector, SelectExpression, NumberFormatType,
+    Message, MessageElement, ParameterSource, ParameterValue, PluralExpression, PluralSelector, SelectCase, SelectExpression, NumberFormatType,
+    DateTimeStyle, CurrencyDisplay, CompactStyle, ListItems, ListStyle, decimal_to_i64,
 };
+use fixed_decimal::{FixedDecimal, RoundingIncrement, Sign, SignDisplay};
+use icu::calendar::types::Time;
+use icu::casemap::CaseMapper;
+use icu::calendar::{Date, Iso};
+use icu::datetime::{DateFormatter, TimeFormatter};
+use icu::datetime::options::length;
 use icu::decimal::FixedDecimalFormatter;
-use icu::decimal::options::FixedDecimalFormatterOptions;
-use icu::experimental::dimension::currency::formatter::{CurrencyFormatter, CurrencyCode};
-use icu::locid::Locale;
+use icu::decimal::options::GroupingStrategy;
+use icu::experimental::compactdecimal::CompactDecimalFormatter;
+use icu::experimental::dimension::provider::currency::{CurrencyEssentialsV1Marker, PatternSelection, PlaceholderValue};
+use icu::experimental::dimension::provider::percent::PercentEssentialsV1Marker;
+use icu::experimental::provider::Baked;
+use icu::list::{ListFormatter, ListLength};
+use icu::locid::extensions::unicode::{Value, key};
+use icu::locid::{Locale, subtags::language};
+use icu::locid_transform::{LocaleDirectionality, LocaleExpander};
+use icu::plurals::{PluralCategory, PluralRuleType, PluralRules};
+use icu_provider::{DataLocale, DataPayload, DataProvider, DataRequest, DataResponse, KeyedDataMarker};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+use tinystr::TinyAsciiStr;
 use writeable::Writeable;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FormatError {
     MissingParameter(String),
-    InvalidParameterType(String),
+    InvalidParameterType {
+        parameter: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    // A plural or select had no case for the value and no `other` to fall back to
+    NoMatchingCase(String),
+    // `format_case` was asked for a selector the plural or select doesn't have
+    UnknownCase {
+        parameter: String,
+        selector: String,
+    },
+    // With `FormatOptions::strict_select`, a select value that no selector other than
+    // the fallback lists
+    UnmatchedSelect {
+        parameter: String,
+        value: String,
+    },
+    // The output passed `FormatOptions::max_output_bytes`
+    OutputTooLarge {
+        limit: usize,
+    },
+    // Locale data or a format option the formatter can't handle
+    Unsupported(String),
+    // The sink passed to `format_message_to` returned `fmt::Error`
+    Write,
 }
 
-impl std::fmt::Display for FormatError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             FormatError::MissingParameter(param) => {
                 write!(f, "Missing parameter: {param}")
             }
-            FormatError::InvalidParameterType(param) => {
-                write!(f, "Invalid parameter type for: {param}")
+            FormatError::InvalidParameterType { parameter, expected, found } => {
+                write!(f, "parameter `{parameter}`: expected {expected}, found {found}")
+            }
+            FormatError::NoMatchingCase(param) => {
+                write!(f, "No matching case for: {param}")
+            }
+            FormatError::UnknownCase { parameter, selector } => {
+                write!(f, "No case `{selector}` for {parameter}")
+            }
+            FormatError::UnmatchedSelect { parameter, value } => {
+                write!(f, "Unlisted select value for {parameter}: {value}")
+            }
+            FormatError::OutputTooLarge { limit } => {
+                write!(f, "Output exceeds {limit} bytes")
+            }
+            FormatError::Unsupported(what) => {
+                write!(f, "Unsupported: {what}")
+            }
+            FormatError::Write => {
+                write!(f, "Failed to write formatted output")
             }
         }
     }
 }
 
-impl std::error::Error for FormatError {}
+impl core::error::Error for FormatError {}
+
+fn type_mismatch(parameter: &str, expected: &'static str, value: ParameterValue) -> FormatError {
+    FormatError::InvalidParameterType { parameter: parameter.to_string(), expected, found: value.type_name() }
+}
+
+fn category_selector(category: PluralCategory) -> PluralSelector {
+    match category {
+        PluralCategory::Zero => PluralSelector::Zero,
+        PluralCategory::One => PluralSelector::One,
+        PluralCategory::Two => PluralSelector::Two,
+        PluralCategory::Few => PluralSelector::Few,
+        PluralCategory::Many => PluralSelector::Many,
+        PluralCategory::Other => PluralSelector::Other,
+    }
+}
+
+// The cardinal plural categories the locale's CLDR rules use, in the order
+// zero, one, two, few, many, other; `other` is always included
+pub fn plural_categories_for(locale: &Locale) -> Result<Vec<PluralSelector>, FormatError> {
+    let cache = FormatterCache::new(locale);
+    Ok(cache.plural_rules()?.categories().map(category_selector).collect())
+}
 
-fn select_plural_case(plural_expr: &PluralExpression, count: i64) -> Option<&Message> {
-    // First, look for exact number matches
+fn select_plural_case<'a>(plural_expr: &'a PluralExpression, count: &FixedDecimal, rules: &PluralRules) -> Option<&'a Message> {
+    // First, look for exact number matches; these compare values, so `=1` matches `1.0`
+    let whole = decimal_to_i64(count);
     for case in &plural_expr.cases {
-        if let PluralSelector::Exact(n) = case.selector {
-            if n == count {
-                return Some(&case.message);
-            }
+        if let PluralSelector::Exact(n) = case.selector
+            && whole == Some(n)
+        {
+            return Some(&case.message);
         }
     }
 
-    // Then apply basic English plural rules
-    let rule = match count {
-        0 => PluralSelector::Zero,
-        1 => PluralSelector::One,
-        2 => PluralSelector::Two,
-        _ => PluralSelector::Other,
-    };
+    // Then ranges, in the order they're written; fractional counts between the bounds match
+    let value = count.to_string().parse::<f64>().ok();
+    for case in &plural_expr.cases {
+        if let PluralSelector::Range(start, end) = case.selector
+            && value.is_some_and(|value| start as f64 <= value && value <= end as f64)
+        {
+            return Some(&case.message);
+        }
+    }
+
+    // Then apply the CLDR plural rules for the formatting locale. Fraction digits are
+    // significant: `1.5` is `other` in English but `one` in French, and `1.0` is `other`
+    let rule = category_selector(rules.category_for(count));
 
     // Look for the matching rule
     for case in &plural_expr.cases {
@@ -63,21 +158,31 @@ fn select_plural_case(plural_expr: &PluralExpression, count: i64) -> Option<&Mes
     None
 }
 
-fn substitute_hash_placeholder(text: &str, count: i64) -> String {
-    text.replace('#', &count.to_string())
+// The case whose selector matches `value`, without falling back
+fn matching_select_case<'a>(select_expr: &'a SelectExpression, value: &str, case_insensitive: bool) -> Option<&'a SelectCase> {
+    // First, look for exact matches
+    if let Some(case) = select_expr.cases.iter().find(|case| case.selector == value) {
+        return Some(case);
+    }
+
+    // Then compare under Unicode case folding, if enabled
+    if case_insensitive {
+        let case_mapper = CaseMapper::new();
+        let folded_value = case_mapper.fold_string(value);
+        return select_expr.cases.iter().find(|case| case_mapper.fold_string(&case.selector) == folded_value);
+    }
+
+    None
 }
 
-fn select_case<'a>(select_expr: &'a SelectExpression, value: &str) -> Option<&'a Message> {
-    // First, look for exact matches
-    for case in &select_expr.cases {
-        if case.selector == value {
-            return Some(&case.message);
-        }
+fn select_case<'a>(select_expr: &'a SelectExpression, value: &str, case_insensitive: bool, fallback: &str) -> Option<&'a Message> {
+    if let Some(case) = matching_select_case(select_expr, value, case_insensitive) {
+        return Some(&case.message);
     }
 
-    // Fall back to "other" if available
+    // Fall back to the catch-all case (`other` unless configured) if available
     for case in &select_expr.cases {
-        if case.selector == "other" {
+        if case.selector == fallback {
             return Some(&case.message);
         }
     }
@@ -85,322 +190,2069 @@ fn select_case<'a>(select_expr: &'a SelectExpression, value: &str) -> Option<&'a
     None
 }
 
-fn format_number(value: f64, format_type: &NumberFormatType, locale: &Locale) -> Result<String, FormatError> {
-    use fixed_decimal::FixedDecimal;
+// Numbers for number arguments, plural counts and `#`, as `ParameterValue::as_decimal`
+// reads them
+fn number_parameter(value: ParameterValue<'_>, context: &str) -> Result<FixedDecimal, FormatError> {
+    value.as_decimal().ok_or_else(|| match value {
+        ParameterValue::Float(_) => type_mismatch(context, "finite number", value),
+        _ => type_mismatch(context, "number", value),
+    })
+}
 
-    match format_type {
-        NumberFormatType::Number => {
-            let formatter = FixedDecimalFormatter::try_new(&locale, FixedDecimalFormatterOptions::default())
-                .map_err(|_| FormatError::InvalidParameterType("number".to_string()))?;
+fn format_decimal(fixed_decimal: &FixedDecimal, grouping: GroupingStrategy, cache: &FormatterCache) -> Result<String, FormatError> {
+    Ok(cache.decimal(grouping)?.format(fixed_decimal).to_string())
+}
 
-            let fixed_decimal = if value.fract() == 0.0 && value >= i64::MIN as f64 && value <= i64::MAX as f64 {
-                FixedDecimal::from(value as i64)
-            } else {
-                let value_str = value.to_string();
-                value_str.parse::<FixedDecimal>()
-                    .map_err(|_| FormatError::InvalidParameterType("number".to_string()))?
-            };
+// CLDR fraction digits for current currencies that don't use the default of two.
+// ICU4X has no data for these, so the table is kept by hand; SLE, which replaced
+// SLL, uses two.
+fn currency_digits(currency: &str) -> i16 {
+    match currency.to_ascii_uppercase().as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "IQD" | "IRR" | "ISK" | "JPY" | "KMF" | "KRW" | "LAK" | "LBP" | "MGA"
+        | "MMK" | "PYG" | "RSD" | "RWF" | "SOS" | "SYP" | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF"
+        | "YER" => 0,
+        "BHD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
 
-            Ok(formatter.format(&fixed_decimal).to_string())
-        }
-        NumberFormatType::Integer => {
-            let formatter = FixedDecimalFormatter::try_new(&locale, FixedDecimalFormatterOptions::default())
-                .map_err(|_| FormatError::InvalidParameterType("number".to_string()))?;
+// Rounds or pads to the currency's fraction digits, so USD 5 is `$5.00`
+fn round_to_currency_digits(fixed_decimal: &mut FixedDecimal, currency: &str, rounding: RoundingMode) {
+    let digits = currency_digits(currency);
+    if fixed_decimal.nonzero_magnitude_end() < -digits {
+        rounding.round(fixed_decimal, -digits);
+    }
+    fixed_decimal.pad_end(-digits);
+}
 
-            let fixed_decimal = FixedDecimal::from(value as i64);
-            Ok(formatter.format(&fixed_decimal).to_string())
-        }
-        NumberFormatType::Percent => {
-            // For now, use simple formatting until we add proper percent formatter
-            let percentage = (value * 100.0) as i64;
-            Ok(format!("{}%", percentage))
+// Renders the amount with the locale's currency pattern. The ICU4X currency formatter
+// only supports symbol widths and writes the raw digits, so we interpolate the
+// locale-formatted number into the CLDR pattern ourselves.
+// Negatives are the locale's minus sign in front of the positive pattern. The baked
+// data has no negative patterns, so locales whose CLDR pattern puts the sign elsewhere,
+// like nl's `¤ -#,##0.00`, still get it in front. In `accounting` style negatives are
+// wrapped in parentheses instead; there are no accounting patterns either, so this is
+// the same for every locale.
+fn format_currency(
+    fixed_decimal: &FixedDecimal,
+    currency: &str,
+    display: CurrencyDisplay,
+    accounting: bool,
+    cache: &FormatterCache,
+) -> Result<String, FormatError> {
+    // Create currency code dynamically from any valid 3-character currency code
+    let currency_code: TinyAsciiStr<3> = if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        let currency_upper = currency.to_uppercase();
+        // Parse the currency string into a TinyAsciiStr
+        match currency_upper.parse() {
+            Ok(tiny_str) => tiny_str,
+            Err(_) => return Err(FormatError::Unsupported(format!("Invalid currency code: {}", currency))),
         }
-        NumberFormatType::Currency(currency) => {
-            let currency_formatter = CurrencyFormatter::try_new(&locale, Default::default())
-                .map_err(|_| FormatError::InvalidParameterType("currency".to_string()))?;
+    } else {
+        return Err(FormatError::Unsupported(format!("Currency code must be 3 ASCII letters: {}", currency)));
+    };
 
-            let fixed_decimal = if value.fract() == 0.0 && value >= i64::MIN as f64 && value <= i64::MAX as f64 {
-                FixedDecimal::from(value as i64)
-            } else {
-                let value_str = value.to_string();
-                value_str.parse::<FixedDecimal>()
-                    .map_err(|_| FormatError::InvalidParameterType("currency".to_string()))?
-            };
+    let essentials = cache.currency_essentials()?.get();
 
-            // Create currency code dynamically from any valid 3-character currency code
-            let currency_code = if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) {
-                let currency_upper = currency.to_uppercase();
-                // Parse the currency string into a TinyAsciiStr and wrap in CurrencyCode
-                match currency_upper.parse() {
-                    Ok(tiny_str) => CurrencyCode(tiny_str),
-                    Err(_) => return Err(FormatError::InvalidParameterType(format!("Invalid currency code: {}", currency))),
-                }
-            } else {
-                return Err(FormatError::InvalidParameterType(format!("Currency code must be 3 ASCII letters: {}", currency)));
-            };
+    let config = essentials
+        .pattern_config_map
+        .get_copied(&currency_code.to_unvalidated())
+        .unwrap_or(essentials.default_pattern_config);
 
-            let formatted = currency_formatter.format_fixed_decimal(&fixed_decimal, currency_code);
+    let (placeholder, pattern_selection) = match display {
+        CurrencyDisplay::Symbol => (config.short_placeholder_value, config.short_pattern_selection),
+        CurrencyDisplay::NarrowSymbol => (config.narrow_placeholder_value, config.narrow_pattern_selection),
+        CurrencyDisplay::Code => (Some(PlaceholderValue::ISO), PatternSelection::StandardAlphaNextToNumber),
+    };
+    let currency_sign = match placeholder {
+        Some(PlaceholderValue::Index(index)) => essentials
+            .placeholders
+            .get(index.into())
+            .ok_or_else(|| FormatError::Unsupported("currency data".to_string()))?,
+        Some(PlaceholderValue::ISO) | None => currency_code.as_str(),
+    };
+    let pattern = match pattern_selection {
+        PatternSelection::Standard => essentials.standard_pattern.as_ref(),
+        PatternSelection::StandardAlphaNextToNumber => essentials
+            .standard_alpha_next_to_number_pattern
+            .as_ref()
+            .or(essentials.standard_pattern.as_ref()),
+    }
+    .ok_or_else(|| FormatError::Unsupported("currency data".to_string()))?;
 
-            // Use write_to method to convert FormattedCurrency to String
-            let mut result = String::new();
-            formatted.write_to(&mut result)
-                .map_err(|_| FormatError::InvalidParameterType("currency formatting".to_string()))?;
-            Ok(result)
-        }
+    let sign = fixed_decimal.sign();
+    let mut magnitude = fixed_decimal.clone();
+    magnitude.set_sign(Sign::None);
+    let number = format_decimal(&magnitude, GroupingStrategy::Auto, cache)?;
+    let formatted = pattern.interpolate((number.as_str(), currency_sign)).write_to_string().into_owned();
+
+    if accounting && sign == Sign::Negative {
+        return Ok(format!("({formatted})"));
     }
+    Ok(locale_sign(sign, cache)? + &formatted)
 }
 
-pub fn format_message<'a>(
-    message: &Message,
-    parameters: Parameters<'a>,
-    locale: &Locale,
-) -> Result<String, FormatError> {
-    let mut result = String::new();
+// The locale's symbol for `sign`, taken from how it formats a signed 1
+fn locale_sign(sign: Sign, cache: &FormatterCache) -> Result<String, FormatError> {
+    if sign == Sign::None {
+        return Ok(String::new());
+    }
+    let mut signed_one = FixedDecimal::from(1);
+    signed_one.set_sign(sign);
+    let one = format_decimal(&FixedDecimal::from(1), GroupingStrategy::Auto, cache)?;
+    Ok(format_decimal(&signed_one, GroupingStrategy::Auto, cache)?.replacen(&one, "", 1))
+}
 
-    for element in &message.elements {
-        match element {
-            MessageElement::Text(text) => {
-                result.push_str(text);
-            }
-            MessageElement::Parameter(param_name) => match parameters.get(param_name) {
-                Some(ParameterValue::String(value)) => result.push_str(value),
-                Some(ParameterValue::Number(value)) => result.push_str(&value.to_string()),
-                None => return Err(FormatError::MissingParameter(param_name.clone())),
-            },
-            MessageElement::Plural(plural_expr) => {
-                let count = match parameters.get(&plural_expr.parameter) {
-                    Some(ParameterValue::Number(n)) => *n,
-                    Some(ParameterValue::String(s)) => match s.parse::<i64>() {
-                        Ok(n) => n,
-                        Err(_) => {
-                            return Err(FormatError::InvalidParameterType(
-                                plural_expr.parameter.clone(),
-                            ));
-                        }
-                    },
-                    None => {
-                        return Err(FormatError::MissingParameter(plural_expr.parameter.clone()));
-                    }
-                };
+// `fixed_decimal` is already in percent, e.g. 75 for `75%`
+fn format_percent(mut fixed_decimal: FixedDecimal, cache: &FormatterCache) -> Result<String, FormatError> {
+    fixed_decimal.trim_start();
+    let is_negative = fixed_decimal.sign() == Sign::Negative;
+    fixed_decimal.set_sign(Sign::None);
 
-                if let Some(selected_message) = select_plural_case(plural_expr, count) {
-                    let formatted_submessage = format_message(selected_message, parameters, locale)?;
-                    let with_substitutions =
-                        substitute_hash_placeholder(&formatted_submessage, count);
-                    result.push_str(&with_substitutions);
-                }
-            }
-            MessageElement::Select(select_expr) => {
-                let value = match parameters.get(&select_expr.parameter) {
-                    Some(ParameterValue::String(s)) => *s,
-                    Some(ParameterValue::Number(_)) => return Err(FormatError::InvalidParameterType(select_expr.parameter.clone())),
-                    None => return Err(FormatError::MissingParameter(select_expr.parameter.clone())),
-                };
+    let essentials = cache.percent_essentials()?.get();
 
-                if let Some(selected_message) = select_case(select_expr, value) {
-                    let formatted_submessage = format_message(selected_message, parameters, locale)?;
-                    result.push_str(&formatted_submessage);
-                }
-            }
-            MessageElement::Number(number_expr) => {
-                let number_value = match parameters.get(&number_expr.parameter) {
-                    Some(ParameterValue::Number(n)) => *n as f64,
-                    Some(ParameterValue::String(s)) => {
-                        match s.parse::<f64>() {
-                            Ok(n) => n,
-                            Err(_) => return Err(FormatError::InvalidParameterType(number_expr.parameter.clone())),
-                        }
-                    }
-                    None => return Err(FormatError::MissingParameter(number_expr.parameter.clone())),
-                };
+    let number = format_decimal(&fixed_decimal, GroupingStrategy::Auto, cache)?;
+    let affixes = &essentials.percent_sign_affixes;
+    let mut result = if essentials.number_index < essentials.percent_symbol_index {
+        format!("{number}{}{}{}", affixes.prefix, essentials.percent_sign_symbol, affixes.suffix)
+    } else {
+        format!("{}{}{}{number}", affixes.prefix, essentials.percent_sign_symbol, affixes.suffix)
+    };
 
-                let formatted_number = format_number(number_value, &number_expr.format_type, locale)?;
-                result.push_str(&formatted_number);
-            }
-        }
+    // The negative pattern is the locale's minus sign in front of the whole positive pattern
+    if is_negative {
+        result.insert_str(0, &locale_sign(Sign::Negative, cache)?);
     }
 
     Ok(result)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::params;
-    use crate::types::{MessageElement, PluralCase, PluralExpression, PluralSelector, SelectCase, SelectExpression, NumberExpression, NumberFormatType};
+// Fraction precision from a `.00`, `.0#`, `.00+` or `.00/w` skeleton stem. Values
+// are rounded to `max` digits with the rounding mode in effect, then zero-padded to
+// `min` digits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FractionDigits {
+    min: i16,
+    max: Option<i16>,
+    hide_if_whole: bool,
+}
 
-    #[test]
-    fn test_format_text_only() {
-        let message = Message::new(vec![MessageElement::Text("Hello world".to_string())]);
-        let params = params!();
+impl FractionDigits {
+    fn parse(stem: &str) -> Option<Self> {
+        let digits = stem.strip_prefix('.')?;
+        let (digits, hide_if_whole) = match digits.strip_suffix("/w") {
+            Some(digits) => (digits, true),
+            None => (digits, false),
+        };
+        let (digits, unlimited) = match digits.strip_suffix('+') {
+            Some(digits) => (digits, true),
+            None => (digits, false),
+        };
 
-        let result = format_message(&message, params);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Hello world");
+        let min = digits.chars().take_while(|c| *c == '0').count();
+        let optional = digits[min..].chars().take_while(|c| *c == '#').count();
+        if min + optional != digits.len() || (unlimited && optional > 0) {
+            return None;
+        }
+
+        Some(Self {
+            min: min as i16,
+            max: if unlimited { None } else { Some((min + optional) as i16) },
+            hide_if_whole,
+        })
     }
 
-    #[test]
-    fn test_format_single_parameter() {
-        let message = Message::new(vec![
-            MessageElement::Text("Hello ".to_string()),
-            MessageElement::Parameter("name".to_string()),
-        ]);
+    fn apply(&self, fixed_decimal: &mut FixedDecimal, rounding: RoundingMode) {
+        if let Some(max) = self.max {
+            rounding.round(fixed_decimal, -max);
+        }
+        // Rounding pads to the rounding position, so trim back before applying the minimum
+        fixed_decimal.trim_end();
+        let is_whole = fixed_decimal.nonzero_magnitude_end() >= 0;
+        if !(self.hide_if_whole && is_whole) {
+            fixed_decimal.pad_end(-self.min);
+        }
+    }
+}
 
-        let result = format_message(&message, params!("name" => "Alice"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Hello Alice");
+// Significant digits from an `@@@`, `@@#` or `@@+` skeleton stem: `@` digits are
+// required and padded with zeros, `#` digits are shown if nonzero, and `+` removes
+// the maximum. `@@@` turns `0.04567` into `0.0457` and `123456` into `123000`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SignificantDigits {
+    min: i16,
+    max: Option<i16>,
+}
+
+impl SignificantDigits {
+    fn parse(stem: &str) -> Option<Self> {
+        let (digits, unlimited) = match stem.strip_suffix('+') {
+            Some(digits) => (digits, true),
+            None => (stem, false),
+        };
+
+        let min = digits.chars().take_while(|c| *c == '@').count();
+        let optional = digits[min..].chars().take_while(|c| *c == '#').count();
+        if min == 0 || min + optional != digits.len() || (unlimited && optional > 0) {
+            return None;
+        }
+
+        Some(Self {
+            min: min as i16,
+            max: (!unlimited).then_some((min + optional) as i16),
+        })
     }
 
-    #[test]
-    fn test_format_multiple_parameters() {
-        let message = Message::new(vec![
-            MessageElement::Text("Hello ".to_string()),
-            MessageElement::Parameter("firstName".to_string()),
-            MessageElement::Text(" ".to_string()),
-            MessageElement::Parameter("lastName".to_string()),
-            MessageElement::Text("!".to_string()),
-        ]);
-        let result = format_message(&message, params!(
-            "firstName" => "Alice",
-            "lastName" => "Johnson"
-        ));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Hello Alice Johnson!");
+    fn apply(&self, fixed_decimal: &mut FixedDecimal, rounding: RoundingMode) {
+        if let Some(max) = self.max
+            && !fixed_decimal.is_zero()
+        {
+            rounding.round(fixed_decimal, fixed_decimal.nonzero_magnitude_start() - max + 1);
+        }
+        fixed_decimal.trim_end();
+        // Measured after rounding, which can carry into a new digit as in `999.7` to `1000`
+        let position = fixed_decimal.nonzero_magnitude_start() - self.min + 1;
+        fixed_decimal.pad_end(position.min(0));
     }
+}
 
-    #[test]
-    fn test_format_missing_parameter() {
-        let message = Message::new(vec![
-            MessageElement::Text("Hello ".to_string()),
-            MessageElement::Parameter("name".to_string()),
-        ]);
-        let params = params!();
+// A `precision-increment/0.05` stem rounds to a multiple of the increment and shows
+// as many fraction digits as the increment is written with, so `1.22` becomes `1.20`.
+// ICU4X only rounds to 1, 2, 5 or 25 times a power of ten, which covers the usual
+// cash increments but not ones like `0.3`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Increment {
+    position: i16,
+    multiple: RoundingIncrement,
+    fraction_digits: i16,
+}
 
-        let result = format_message(&message, params);
-        assert!(result.is_err());
-        assert_eq!(
-            result.unwrap_err(),
-            FormatError::MissingParameter("name".to_string())
-        );
+impl Increment {
+    fn parse(increment: &str) -> Option<Self> {
+        let increment: FixedDecimal = increment.parse().ok()?;
+        if increment.sign() != Sign::None || increment.is_zero() {
+            return None;
+        }
+        let position = increment.nonzero_magnitude_end();
+        let leading = increment.digit_at(increment.nonzero_magnitude_start());
+        let multiple = match (increment.nonzero_magnitude_start() - position, leading) {
+            (0, 1) => RoundingIncrement::MultiplesOf1,
+            (0, 2) => RoundingIncrement::MultiplesOf2,
+            (0, 5) => RoundingIncrement::MultiplesOf5,
+            (1, 2) if increment.digit_at(position) == 5 => RoundingIncrement::MultiplesOf25,
+            _ => return None,
+        };
+
+        let lowest = *increment.magnitude_range().start();
+        Some(Self { position, multiple, fraction_digits: -lowest.min(0) })
     }
 
-    #[test]
-    fn test_format_plural_one() {
-        let plural_expr = PluralExpression {
-            parameter: "count".to_string(),
-            cases: vec![
-                PluralCase {
-                    selector: PluralSelector::One,
-                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
-                },
-                PluralCase {
-                    selector: PluralSelector::Other,
-                    message: Message::new(vec![MessageElement::Text("# items".to_string())]),
-                },
-            ],
-        };
-        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
-        let result = format_message(&message, params!("count" => 1));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "1 item");
+    fn apply(&self, fixed_decimal: &mut FixedDecimal, rounding: RoundingMode) {
+        rounding.round_to_increment(fixed_decimal, self.position, self.multiple);
+        fixed_decimal.trim_end();
+        fixed_decimal.pad_end(-self.fraction_digits);
     }
+}
 
-    #[test]
-    fn test_format_plural_other() {
-        let plural_expr = PluralExpression {
-            parameter: "count".to_string(),
-            cases: vec![
-                PluralCase {
-                    selector: PluralSelector::One,
-                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
-                },
-                PluralCase {
-                    selector: PluralSelector::Other,
-                    message: Message::new(vec![MessageElement::Text("# items".to_string())]),
-                },
-            ],
-        };
-        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
-        let result = format_message(&message, params!("count" => 5));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "5 items");
+// The precision stem of a skeleton; the last one given wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Precision {
+    Fraction(FractionDigits),
+    Significant(SignificantDigits),
+    Increment(Increment),
+}
+
+impl Precision {
+    fn parse(stem: &str) -> Option<Self> {
+        FractionDigits::parse(stem)
+            .map(Precision::Fraction)
+            .or_else(|| SignificantDigits::parse(stem).map(Precision::Significant))
     }
 
-    #[test]
-    fn test_format_plural_with_context() {
-        let plural_expr = PluralExpression {
-            parameter: "count".to_string(),
-            cases: vec![
-                PluralCase {
-                    selector: PluralSelector::One,
-                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
-                },
-                PluralCase {
-                    selector: PluralSelector::Other,
-                    message: Message::new(vec![MessageElement::Text("# items".to_string())]),
-                },
-            ],
-        };
-        let message = Message::new(vec![
-            MessageElement::Text("You have ".to_string()),
-            MessageElement::Plural(plural_expr),
-            MessageElement::Text(" in your cart.".to_string()),
-        ]);
-        let result = format_message(&message, params!("count" => 3));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "You have 3 items in your cart.");
+    fn apply(&self, fixed_decimal: &mut FixedDecimal, rounding: RoundingMode) {
+        match self {
+            Precision::Fraction(fraction_digits) => fraction_digits.apply(fixed_decimal, rounding),
+            Precision::Significant(significant_digits) => significant_digits.apply(fixed_decimal, rounding),
+            Precision::Increment(increment) => increment.apply(fixed_decimal, rounding),
+        }
     }
+}
 
-    #[test]
-    fn test_format_select_male() {
-        let select_expr = SelectExpression {
-            parameter: "gender".to_string(),
-            cases: vec![
-                SelectCase {
-                    selector: "male".to_string(),
-                    message: Message::new(vec![MessageElement::Text("He likes this.".to_string())]),
-                },
-                SelectCase {
-                    selector: "female".to_string(),
-                    message: Message::new(vec![MessageElement::Text("She likes this.".to_string())]),
-                },
-                SelectCase {
-                    selector: "other".to_string(),
-                    message: Message::new(vec![MessageElement::Text("They like this.".to_string())]),
-                },
-            ],
+// Integer digits from an `integer-width/*00` or `integer-width/##00` stem: zeros are
+// required digits, padded with leading zeros, and without `*` the `#` and `0` count
+// is a maximum above which higher digits are dropped, as in ICU
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IntegerWidth {
+    min: i16,
+    max: Option<i16>,
+}
+
+impl IntegerWidth {
+    fn parse(stem: &str) -> Option<Self> {
+        let digits = stem.strip_prefix("integer-width/")?;
+        let (digits, unlimited) = match digits.strip_prefix('*') {
+            Some(digits) => (digits, true),
+            None => (digits, false),
         };
-        let message = Message::new(vec![MessageElement::Select(select_expr)]);
 
-        let result = format_message(&message, params!("gender" => "male"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "He likes this.");
+        let optional = digits.chars().take_while(|c| *c == '#').count();
+        let min = digits[optional..].chars().take_while(|c| *c == '0').count();
+        if optional + min != digits.len() || (unlimited && optional > 0) {
+            return None;
+        }
+
+        Some(Self {
+            min: min as i16,
+            max: (!unlimited).then_some((optional + min) as i16),
+        })
     }
 
-    #[test]
-    fn test_format_select_female() {
-        let select_expr = SelectExpression {
-            parameter: "gender".to_string(),
-            cases: vec![
-                SelectCase {
-                    selector: "male".to_string(),
-                    message: Message::new(vec![MessageElement::Text("He likes this.".to_string())]),
-                },
-                SelectCase {
-                    selector: "female".to_string(),
-                    message: Message::new(vec![MessageElement::Text("She likes this.".to_string())]),
-                },
-                SelectCase {
-                    selector: "other".to_string(),
-                    message: Message::new(vec![MessageElement::Text("They like this.".to_string())]),
-                },
-            ],
+    fn apply(&self, fixed_decimal: &mut FixedDecimal) {
+        if let Some(max) = self.max {
+            fixed_decimal.set_max_position(max);
+            fixed_decimal.trim_start();
+        }
+        fixed_decimal.pad_start(self.min);
+    }
+}
+
+// `pad/N` right-aligns the formatted number in N columns and `pad-end/N` left-aligns
+// it, both with spaces unless a fill character follows, as in `pad/6/*`. Columns are
+// characters of the final string, so grouping separators, signs and currency symbols
+// count towards the width and a grouped `1,234` takes five of them; values already
+// at least N wide are left as they are. Use `integer-width` to pad digits with zeros.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Padding {
+    width: usize,
+    fill: char,
+    at_end: bool,
+}
+
+impl Padding {
+    fn parse(stem: &str) -> Option<Self> {
+        let (options, at_end) = match stem.strip_prefix("pad/") {
+            Some(options) => (options, false),
+            None => (stem.strip_prefix("pad-end/")?, true),
+        };
+        let (width, fill) = match options.split_once('/') {
+            Some((width, fill)) => {
+                let mut chars = fill.chars();
+                let fill = chars.next().filter(|_| chars.next().is_none())?;
+                (width, fill)
+            }
+            None => (options, ' '),
         };
-        let message = Message::new(vec![MessageElement::Select(select_expr)]);
 
-        let result = format_message(&message, params!("gender" => "female"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "She likes this.");
+        Some(Self { width: width.parse().ok()?, fill, at_end })
+    }
+
+    fn apply(&self, formatted: &mut String) {
+        let missing = self.width.saturating_sub(formatted.chars().count());
+        let fill: String = core::iter::repeat_n(self.fill, missing).collect();
+        if self.at_end {
+            formatted.push_str(&fill);
+        } else {
+            formatted.insert_str(0, &fill);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SkeletonOptions {
+    precision: Option<Precision>,
+    integer_width: Option<IntegerWidth>,
+    grouping_off: bool,
+    compact: Option<CompactStyle>,
+    currency: Option<String>,
+    currency_display: CurrencyDisplay,
+    sign_display: SignDisplay,
+    accounting: bool,
+    // 1 for `scientific`, 3 for `engineering`
+    exponent_step: Option<i16>,
+    rounding_mode: Option<RoundingMode>,
+    percent: bool,
+    // The power of ten from `scale/N`; a bare `percent` scales by 100
+    scale: Option<i16>,
+    // The name and zero digit from `numbering-system/<name>`
+    numbering_system: Option<(&'static str, char)>,
+    padding: Option<Padding>,
+    approximately: bool,
+    // From `locale/<tag>`, formatting this one argument as if the message were in it
+    locale: Option<Locale>,
+}
+
+impl Default for SkeletonOptions {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            integer_width: None,
+            grouping_off: false,
+            compact: None,
+            currency: None,
+            currency_display: CurrencyDisplay::Symbol,
+            sign_display: SignDisplay::Auto,
+            accounting: false,
+            exponent_step: None,
+            rounding_mode: None,
+            percent: false,
+            scale: None,
+            numbering_system: None,
+            padding: None,
+            approximately: false,
+            locale: None,
+        }
+    }
+}
+
+impl SkeletonOptions {
+    fn parse(skeleton: &str) -> Result<Self, FormatError> {
+        let mut options = SkeletonOptions::default();
+
+        for token in skeleton.split_whitespace() {
+            if let Some(precision) = Precision::parse(token) {
+                options.precision = Some(precision);
+            } else if let Some(increment) = token.strip_prefix("precision-increment/") {
+                let increment = Increment::parse(increment).ok_or_else(|| {
+                    FormatError::Unsupported(format!("Rounding increment must be 1, 2, 5 or 25 times a power of ten: {increment}"))
+                })?;
+                options.precision = Some(Precision::Increment(increment));
+            } else if let Some(integer_width) = IntegerWidth::parse(token) {
+                options.integer_width = Some(integer_width);
+            } else if token == "group-off" {
+                options.grouping_off = true;
+            } else if token == "compact-short" {
+                options.compact = Some(CompactStyle::Short);
+            } else if token == "compact-long" {
+                options.compact = Some(CompactStyle::Long);
+            } else if let Some(currency) = token.strip_prefix("currency/") {
+                options.currency = Some(currency.to_string());
+            } else if token == "unit-width-short" {
+                options.currency_display = CurrencyDisplay::Symbol;
+            } else if token == "unit-width-narrow" {
+                options.currency_display = CurrencyDisplay::NarrowSymbol;
+            } else if token == "unit-width-iso-code" {
+                options.currency_display = CurrencyDisplay::Code;
+            } else if token == "sign-auto" {
+                options.sign_display = SignDisplay::Auto;
+            } else if token == "sign-always" {
+                options.sign_display = SignDisplay::Always;
+            } else if token == "sign-never" {
+                options.sign_display = SignDisplay::Never;
+            } else if token == "sign-except-zero" {
+                options.sign_display = SignDisplay::ExceptZero;
+            } else if token == "sign-accounting" {
+                options.accounting = true;
+            } else if token == "scientific" {
+                options.exponent_step = Some(1);
+            } else if token == "engineering" {
+                options.exponent_step = Some(3);
+            } else if let Some(mode) = token.strip_prefix("rounding-mode-").and_then(RoundingMode::parse) {
+                options.rounding_mode = Some(mode);
+            } else if token == "percent" {
+                options.percent = true;
+            } else if let Some(scale) = token.strip_prefix("scale/") {
+                let power = parse_scale(scale)
+                    .ok_or_else(|| FormatError::Unsupported(format!("Number scale must be a power of ten: {scale}")))?;
+                options.scale = Some(power);
+            } else if let Some(padding) = Padding::parse(token) {
+                options.padding = Some(padding);
+            } else if token == "approximately" {
+                options.approximately = true;
+            } else if let Some(tag) = token.strip_prefix("locale/") {
+                let locale = tag.parse().map_err(|_| FormatError::Unsupported(format!("Invalid locale: {tag}")))?;
+                options.locale = Some(locale);
+            } else if let Some(name) = token.strip_prefix("numbering-system/") {
+                let system = numbering_system(name)
+                    .ok_or_else(|| FormatError::Unsupported(format!("Unknown numbering system: {name}")))?;
+                options.numbering_system = Some(system);
+            } else {
+                return Err(FormatError::Unsupported(format!("Unknown number skeleton token: {token}")));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Only powers of ten can be applied exactly to a `FixedDecimal`, e.g. `100` or `0.001`
+fn parse_scale(scale: &str) -> Option<i16> {
+    let scale: FixedDecimal = scale.parse().ok()?;
+    let power = scale.nonzero_magnitude_start();
+    let is_power = scale.sign() == Sign::None && power == scale.nonzero_magnitude_end() && scale.digit_at(power) == 1;
+    is_power.then_some(power)
+}
+
+// Numbering systems with ten contiguous decimal digits, by CLDR name
+fn numbering_system(name: &str) -> Option<(&'static str, char)> {
+    const SYSTEMS: [(&str, char); 18] = [
+        ("arab", '\u{660}'),
+        ("arabext", '\u{6f0}'),
+        ("beng", '\u{9e6}'),
+        ("deva", '\u{966}'),
+        ("fullwide", '\u{ff10}'),
+        ("gujr", '\u{ae6}'),
+        ("guru", '\u{a66}'),
+        ("khmr", '\u{17e0}'),
+        ("knda", '\u{ce6}'),
+        ("laoo", '\u{ed0}'),
+        ("latn", '0'),
+        ("mlym", '\u{d66}'),
+        ("mymr", '\u{1040}'),
+        ("orya", '\u{b66}'),
+        ("tamldec", '\u{be6}'),
+        ("telu", '\u{c66}'),
+        ("thai", '\u{e50}'),
+        ("tibt", '\u{f20}'),
+    ];
+    SYSTEMS.into_iter().find(|(system, _)| *system == name)
+}
+
+// Rewrites ASCII digits into the numbering system that starts at `zero`
+fn transliterate_digits(formatted: &str, zero: char) -> String {
+    formatted
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) if c.is_ascii_digit() => char::from_u32(zero as u32 + digit).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+// CLDR's `approximatelySign` isn't in ICU4X's decimal data; nearly every locale
+// inherits the root value
+const APPROXIMATELY_SIGN: &str = "~";
+
+fn format_skeleton(
+    fixed_decimal: FixedDecimal,
+    skeleton: &str,
+    rounding: RoundingMode,
+    cache: &FormatterCache,
+) -> Result<String, FormatError> {
+    let options = SkeletonOptions::parse(skeleton)?;
+    let outer = cache;
+    let cache = match &options.locale {
+        Some(locale) => outer.for_locale(locale),
+        None => outer,
+    };
+    let mut formatted = match options.numbering_system {
+        None => format_skeleton_options(fixed_decimal, &options, rounding, cache)?,
+        Some((name, zero)) => {
+            // Locales with data for the system also get its separators, like `٬` in Arabic;
+            // elsewhere ICU4X falls back to Latin digits, which are transliterated as in ICU
+            let mut locale = cache.locale().clone();
+            let value = Value::try_from_bytes(name.as_bytes())
+                .map_err(|_| FormatError::Unsupported(format!("Unknown numbering system: {name}")))?;
+            locale.extensions.unicode.keywords.set(key!("nu"), value);
+            let formatted = format_skeleton_options(fixed_decimal, &options, rounding, outer.for_locale(&locale))?;
+            transliterate_digits(&formatted, zero)
+        }
+    };
+
+    if options.approximately {
+        formatted.insert_str(0, APPROXIMATELY_SIGN);
+    }
+    if let Some(padding) = options.padding {
+        padding.apply(&mut formatted);
+    }
+    Ok(formatted)
+}
+
+fn format_skeleton_options(
+    mut fixed_decimal: FixedDecimal,
+    options: &SkeletonOptions,
+    rounding: RoundingMode,
+    cache: &FormatterCache,
+) -> Result<String, FormatError> {
+    let rounding = options.rounding_mode.unwrap_or(rounding);
+
+    // Precision applies to the scaled value, so `percent .0` keeps one digit of the percentage
+    match options.scale {
+        Some(power) => {
+            fixed_decimal.multiply_pow10(power);
+            // Integer zeros shifted past the decimal point would show as `2.500`
+            if power < 0 {
+                fixed_decimal.trim_end();
+            }
+        }
+        None if options.percent => fixed_decimal.multiply_pow10(2),
+        None => {}
+    }
+
+    // The precision stem applies to the mantissa
+    if let Some(exponent_step) = options.exponent_step {
+        fixed_decimal.apply_sign_display(options.sign_display);
+        return format_exponential(fixed_decimal, exponent_step, options.precision, rounding, cache);
+    }
+
+    if let Some(precision) = options.precision {
+        precision.apply(&mut fixed_decimal, rounding);
+    }
+    if let Some(integer_width) = options.integer_width {
+        integer_width.apply(&mut fixed_decimal);
+    }
+    fixed_decimal.apply_sign_display(options.sign_display);
+
+    if options.percent {
+        return format_percent(fixed_decimal, cache);
+    }
+
+    if let Some(currency) = &options.currency {
+        // An explicit precision stem overrides the currency's own digits
+        if options.precision.is_none() {
+            round_to_currency_digits(&mut fixed_decimal, currency, rounding);
+        }
+        return format_currency(&fixed_decimal, currency, options.currency_display, options.accounting, cache);
+    }
+
+    let grouping = if options.grouping_off { GroupingStrategy::Never } else { GroupingStrategy::Auto };
+
+    if let Some(style) = options.compact {
+        return format_compact(fixed_decimal, style, grouping, cache);
+    }
+
+    format_decimal(&fixed_decimal, grouping, cache)
+}
+
+// Formats `m×10^e` as `mEe`, with `e` a multiple of `exponent_step` and `m` as small
+// as that allows while staying at least 1: `4.2E4` for steps of 1, `42E3` for steps
+// of 3. CLDR's exponent separator is `E` for nearly every locale and isn't in the
+// baked decimal data, so it is fixed; the digits and signs are localized.
+fn format_exponential(
+    mut fixed_decimal: FixedDecimal,
+    exponent_step: i16,
+    precision: Option<Precision>,
+    rounding: RoundingMode,
+    cache: &FormatterCache,
+) -> Result<String, FormatError> {
+    let mut exponent = 0;
+    if !fixed_decimal.is_zero() {
+        exponent = fixed_decimal.nonzero_magnitude_start().div_euclid(exponent_step) * exponent_step;
+        fixed_decimal.multiply_pow10(-exponent);
+        fixed_decimal.trim_start();
+        fixed_decimal.trim_end();
+    }
+    if let Some(precision) = precision {
+        precision.apply(&mut fixed_decimal, rounding);
+        // Rounding up can carry into a new integer digit, e.g. `9.996` to `10.00`
+        if fixed_decimal.nonzero_magnitude_start() >= exponent_step {
+            exponent += exponent_step;
+            fixed_decimal.multiply_pow10(-exponent_step);
+            fixed_decimal.trim_end();
+            precision.apply(&mut fixed_decimal, rounding);
+        }
+    }
+
+    let mantissa = format_decimal(&fixed_decimal, GroupingStrategy::Never, cache)?;
+    let exponent = format_decimal(&FixedDecimal::from(exponent), GroupingStrategy::Never, cache)?;
+    Ok(format!("{mantissa}E{exponent}"))
+}
+
+// Values below 1000 have no compact form and are formatted as plain decimals
+fn format_compact(fixed_decimal: FixedDecimal, style: CompactStyle, grouping: GroupingStrategy, cache: &FormatterCache) -> Result<String, FormatError> {
+    if fixed_decimal.nonzero_magnitude_start() < 3 {
+        return format_decimal(&fixed_decimal, grouping, cache);
+    }
+
+    let formatter = cache.compact(style, grouping)?;
+    Ok(formatter.format_fixed_decimal(fixed_decimal).write_to_string().into_owned())
+}
+
+// Stable `OnceCell::get_or_try_init` substitute: errors leave the cell empty so the
+// next call retries
+fn get_or_try_init<T>(cell: &OnceCell<T>, init: impl FnOnce() -> Result<T, FormatError>) -> Result<&T, FormatError> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+fn grouping_index(grouping: GroupingStrategy) -> usize {
+    match grouping {
+        GroupingStrategy::Never => 1,
+        _ => 0,
+    }
+}
+
+fn style_index(style: DateTimeStyle) -> usize {
+    match style {
+        DateTimeStyle::Short => 0,
+        DateTimeStyle::Medium => 1,
+        DateTimeStyle::Long => 2,
+        DateTimeStyle::Full => 3,
+    }
+}
+
+// ICU formatters and CLDR data for a single locale, each constructed on first use
+// and then reused. Cells are keyed by the options that change the constructed value.
+pub(crate) struct FormatterCache {
+    locale: Locale,
+    data_locale: DataLocale,
+    decimal: [OnceCell<FixedDecimalFormatter>; 2],
+    compact_short: [OnceCell<CompactDecimalFormatter>; 2],
+    compact_long: [OnceCell<CompactDecimalFormatter>; 2],
+    currency_essentials: OnceCell<DataPayload<CurrencyEssentialsV1Marker>>,
+    percent_essentials: OnceCell<DataPayload<PercentEssentialsV1Marker>>,
+    plural_rules: OnceCell<PluralRules>,
+    ordinal_rules: OnceCell<PluralRules>,
+    date: [OnceCell<DateFormatter>; 4],
+    time: [OnceCell<TimeFormatter>; 4],
+    list: [OnceCell<ListFormatter>; 3],
+    right_to_left: OnceCell<bool>,
+    default_currency: OnceCell<&'static str>,
+    // The next locale to try for data this one can't provide
+    fallback: Option<Box<FormatterCache>>,
+    // Caches for the locales of `locale/<tag>` and `numbering-system/<name>` skeletons,
+    // chained through this field
+    other_locales: OnceCell<Box<FormatterCache>>,
+}
+
+impl FormatterCache {
+    pub(crate) fn new(locale: &Locale) -> Self {
+        Self {
+            locale: locale.clone(),
+            data_locale: DataLocale::from(locale),
+            decimal: Default::default(),
+            compact_short: Default::default(),
+            compact_long: Default::default(),
+            currency_essentials: OnceCell::new(),
+            percent_essentials: OnceCell::new(),
+            plural_rules: OnceCell::new(),
+            ordinal_rules: OnceCell::new(),
+            date: Default::default(),
+            time: Default::default(),
+            list: Default::default(),
+            right_to_left: OnceCell::new(),
+            default_currency: OnceCell::new(),
+            fallback: None,
+            other_locales: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn with_fallbacks(locale: &Locale, fallbacks: &[Locale]) -> Self {
+        let mut cache = Self::new(locale);
+        cache.fallback = fallbacks.split_first().map(|(next, rest)| Box::new(Self::with_fallbacks(next, rest)));
+        cache
+    }
+
+    pub(crate) fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    // The cache for `locale`, created on first use with the same fallbacks as this one
+    // and then kept, so each locale's formatters are only constructed once
+    fn for_locale(&self, locale: &Locale) -> &FormatterCache {
+        if *locale == self.locale {
+            return self;
+        }
+        let mut link = &self.other_locales;
+        loop {
+            let cache = link.get_or_init(|| {
+                let mut fallbacks = Vec::new();
+                let mut next = self.fallback.as_deref();
+                while let Some(fallback) = next {
+                    fallbacks.push(fallback.locale.clone());
+                    next = fallback.fallback.as_deref();
+                }
+                Box::new(FormatterCache::with_fallbacks(locale, &fallbacks))
+            });
+            if cache.locale == *locale {
+                return cache;
+            }
+            link = &cache.other_locales;
+        }
+    }
+
+    // On failure, asks the rest of the fallback chain; the error of the last locale is kept
+    fn or_fallback<'a, R>(
+        &'a self,
+        result: Result<R, FormatError>,
+        next: impl FnOnce(&'a FormatterCache) -> Result<R, FormatError>,
+    ) -> Result<R, FormatError> {
+        match (result, &self.fallback) {
+            (Err(_), Some(fallback)) => next(fallback),
+            (result, _) => result,
+        }
+    }
+
+    fn is_right_to_left(&self) -> bool {
+        *self.right_to_left.get_or_init(|| LocaleDirectionality::new().is_right_to_left(&self.locale))
+    }
+
+    // The currency of the locale's region, or of its likely region when it has none
+    fn default_currency(&self) -> Result<&'static str, FormatError> {
+        let result = get_or_try_init(&self.default_currency, || {
+            let mut locale = self.locale.clone();
+            LocaleExpander::new().maximize(&mut locale);
+            let region = locale.id.region.ok_or_else(|| FormatError::Unsupported(format!("default currency for {}", self.locale)))?;
+            region_currency(region.as_str())
+                .ok_or_else(|| FormatError::Unsupported(format!("default currency for region {region}")))
+        });
+        self.or_fallback(result.copied(), FormatterCache::default_currency)
+    }
+
+    fn decimal(&self, grouping: GroupingStrategy) -> Result<&FixedDecimalFormatter, FormatError> {
+        let result = get_or_try_init(&self.decimal[grouping_index(grouping)], || {
+            FixedDecimalFormatter::try_new(&self.data_locale, grouping.into())
+                .map_err(|_| FormatError::Unsupported("number data".to_string()))
+        });
+        self.or_fallback(result, |fallback| fallback.decimal(grouping))
+    }
+
+    fn compact(&self, style: CompactStyle, grouping: GroupingStrategy) -> Result<&CompactDecimalFormatter, FormatError> {
+        let cell = match style {
+            CompactStyle::Short => &self.compact_short[grouping_index(grouping)],
+            CompactStyle::Long => &self.compact_long[grouping_index(grouping)],
+        };
+        let result = get_or_try_init(cell, || {
+            match style {
+                CompactStyle::Short => CompactDecimalFormatter::try_new_short(&self.data_locale, grouping.into()),
+                CompactStyle::Long => CompactDecimalFormatter::try_new_long(&self.data_locale, grouping.into()),
+            }
+            .map_err(|_| FormatError::Unsupported("compact data".to_string()))
+        });
+        self.or_fallback(result, |fallback| fallback.compact(style, grouping))
+    }
+
+    fn currency_essentials(&self) -> Result<&DataPayload<CurrencyEssentialsV1Marker>, FormatError> {
+        let result = get_or_try_init(&self.currency_essentials, || self.load("currency"));
+        self.or_fallback(result, FormatterCache::currency_essentials)
+    }
+
+    fn percent_essentials(&self) -> Result<&DataPayload<PercentEssentialsV1Marker>, FormatError> {
+        let result = get_or_try_init(&self.percent_essentials, || self.load("percent"));
+        self.or_fallback(result, FormatterCache::percent_essentials)
+    }
+
+    fn load<M: KeyedDataMarker>(&self, context: &str) -> Result<DataPayload<M>, FormatError>
+    where
+        Baked: DataProvider<M>,
+    {
+        let response: DataResponse<M> = Baked
+            .load(DataRequest { locale: &self.data_locale, metadata: Default::default() })
+            .map_err(|_| FormatError::Unsupported(format!("{context} data")))?;
+        response
+            .take_payload()
+            .map_err(|_| FormatError::Unsupported(format!("{context} data")))
+    }
+
+    fn plural_rules(&self) -> Result<&PluralRules, FormatError> {
+        let result = get_or_try_init(&self.plural_rules, || {
+            PluralRules::try_new(&self.data_locale, PluralRuleType::Cardinal)
+                .map_err(|_| FormatError::Unsupported("plural data".to_string()))
+        });
+        self.or_fallback(result, FormatterCache::plural_rules)
+    }
+
+    fn ordinal_rules(&self) -> Result<&PluralRules, FormatError> {
+        let result = get_or_try_init(&self.ordinal_rules, || {
+            PluralRules::try_new(&self.data_locale, PluralRuleType::Ordinal)
+                .map_err(|_| FormatError::Unsupported("ordinal data".to_string()))
+        });
+        self.or_fallback(result, FormatterCache::ordinal_rules)
+    }
+
+    fn date(&self, style: DateTimeStyle) -> Result<&DateFormatter, FormatError> {
+        let result = get_or_try_init(&self.date[style_index(style)], || {
+            let length = match style {
+                DateTimeStyle::Short => length::Date::Short,
+                DateTimeStyle::Medium => length::Date::Medium,
+                DateTimeStyle::Long => length::Date::Long,
+                DateTimeStyle::Full => length::Date::Full,
+            };
+            DateFormatter::try_new_with_length(&self.data_locale, length)
+                .map_err(|_| FormatError::Unsupported("date data".to_string()))
+        });
+        self.or_fallback(result, |fallback| fallback.date(style))
+    }
+
+    fn time(&self, style: DateTimeStyle) -> Result<&TimeFormatter, FormatError> {
+        let result = get_or_try_init(&self.time[style_index(style)], || {
+            let length = match style {
+                DateTimeStyle::Short => length::Time::Short,
+                DateTimeStyle::Medium => length::Time::Medium,
+                DateTimeStyle::Long => length::Time::Long,
+                DateTimeStyle::Full => length::Time::Full,
+            };
+            TimeFormatter::try_new_with_length(&self.data_locale, length)
+                .map_err(|_| FormatError::Unsupported("time data".to_string()))
+        });
+        self.or_fallback(result, |fallback| fallback.time(style))
+    }
+
+    fn list(&self, style: ListStyle) -> Result<&ListFormatter, FormatError> {
+        let index = match style {
+            ListStyle::And => 0,
+            ListStyle::Or => 1,
+            ListStyle::Unit => 2,
+        };
+        let result = get_or_try_init(&self.list[index], || {
+            match style {
+                ListStyle::And => ListFormatter::try_new_and_with_length(&self.data_locale, ListLength::Wide),
+                ListStyle::Or => ListFormatter::try_new_or_with_length(&self.data_locale, ListLength::Wide),
+                ListStyle::Unit => ListFormatter::try_new_unit_with_length(&self.data_locale, ListLength::Wide),
+            }
+            .map_err(|_| FormatError::Unsupported("list data".to_string()))
+        });
+        self.or_fallback(result, |fallback| fallback.list(style))
+    }
+}
+
+fn format_number(
+    mut fixed_decimal: FixedDecimal,
+    format_type: &NumberFormatType,
+    rounding: RoundingMode,
+    cache: &FormatterCache,
+) -> Result<String, FormatError> {
+    match format_type {
+        NumberFormatType::Number => format_decimal(&fixed_decimal, GroupingStrategy::Auto, cache),
+        NumberFormatType::Integer => {
+            // Truncates toward zero; a truncated `-0.5` renders as `0`, not `-0`
+            fixed_decimal.trunc(0);
+            fixed_decimal.trim_end();
+            if fixed_decimal.is_zero() {
+                fixed_decimal.set_sign(Sign::None);
+            }
+            format_decimal(&fixed_decimal, GroupingStrategy::Auto, cache)
+        }
+        NumberFormatType::Percent => {
+            fixed_decimal.multiply_pow10(2);
+            format_percent(fixed_decimal, cache)
+        }
+        NumberFormatType::Currency(currency, display) => {
+            let currency = match currency {
+                Some(currency) => currency.as_str(),
+                None => cache.default_currency()?,
+            };
+            round_to_currency_digits(&mut fixed_decimal, currency, rounding);
+            format_currency(&fixed_decimal, currency, *display, false, cache)
+        }
+        NumberFormatType::Compact(style) => format_compact(fixed_decimal, *style, GroupingStrategy::Auto, cache),
+        NumberFormatType::Skeleton(skeleton) => format_skeleton(fixed_decimal, skeleton, rounding, cache),
+        NumberFormatType::Spellout => format_spellout(&fixed_decimal, cache),
+        NumberFormatType::Ordinal => format_ordinal(fixed_decimal, cache),
+        NumberFormatType::Scientific => format_exponential(fixed_decimal, 1, None, rounding, cache),
+        NumberFormatType::Engineering => format_exponential(fixed_decimal, 3, None, rounding, cache),
+    }
+}
+
+// The suffix is chosen by the locale's CLDR ordinal plural category. CLDR has no
+// suffix data outside of rule-based formatting, so the category-to-suffix mapping is
+// kept here; languages without an entry render the bare number.
+fn format_ordinal(mut fixed_decimal: FixedDecimal, cache: &FormatterCache) -> Result<String, FormatError> {
+    fixed_decimal.trim_end();
+    let number = format_decimal(&fixed_decimal, GroupingStrategy::Auto, cache)?;
+
+    let category = cache.ordinal_rules()?.category_for(&fixed_decimal);
+    let suffix = ordinal_suffix(cache.locale().id.language.as_str(), category);
+    Ok(number + suffix)
+}
+
+fn ordinal_suffix(language: &str, category: PluralCategory) -> &'static str {
+    match (language, category) {
+        ("en", PluralCategory::One) => "st",
+        ("en", PluralCategory::Two) => "nd",
+        ("en", PluralCategory::Few) => "rd",
+        ("en", _) => "th",
+        ("fr", PluralCategory::One) => "er",
+        ("fr", _) => "e",
+        ("de" | "da" | "fi" | "nb" | "no" | "cs" | "sk" | "pl" | "hu" | "tr", _) => ".",
+        // Masculine only: the feminine `ª` depends on the noun, which the number doesn't know
+        ("es" | "it" | "pt" | "gl", _) => "º",
+        ("nl", _) => "e",
+        ("sv", PluralCategory::One) => ":a",
+        ("sv", _) => ":e",
+        _ => "",
+    }
+}
+
+// ICU4X has no rule-based number formatter yet, so spellout is implemented here for
+// English only. Other locales fall back to the plain locale-formatted digits.
+fn format_spellout(fixed_decimal: &FixedDecimal, cache: &FormatterCache) -> Result<String, FormatError> {
+    if cache.locale().id.language == language!("en")
+        && let Some(words) = spell_out_english(fixed_decimal)
+    {
+        return Ok(words);
+    }
+    format_decimal(fixed_decimal, GroupingStrategy::Auto, cache)
+}
+
+const ENGLISH_ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const ENGLISH_TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const ENGLISH_SCALES: [&str; 13] = [
+    "", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion", "sextillion",
+    "septillion", "octillion", "nonillion", "decillion", "undecillion",
+];
+
+// Follows CLDR `spellout-numbering`: `one hundred twenty-three`, `minus five`, `one point five`
+fn spell_out_english(fixed_decimal: &FixedDecimal) -> Option<String> {
+    let magnitudes = fixed_decimal.magnitude_range();
+    let mut integer: u128 = 0;
+    for magnitude in (0..=(*magnitudes.end()).max(0)).rev() {
+        integer = integer.checked_mul(10)?.checked_add(fixed_decimal.digit_at(magnitude).into())?;
+    }
+
+    let mut words = Vec::new();
+    if fixed_decimal.sign() == Sign::Negative && !fixed_decimal.is_zero() {
+        words.push("minus".to_string());
+    }
+
+    if integer == 0 {
+        words.push(ENGLISH_ONES[0].to_string());
+    } else {
+        let mut groups = Vec::new();
+        while integer > 0 {
+            groups.push((integer % 1000) as usize);
+            integer /= 1000;
+        }
+        for (scale, group) in groups.iter().enumerate().rev() {
+            if *group == 0 {
+                continue;
+            }
+            words.push(spell_out_english_below_thousand(*group));
+            words.push(ENGLISH_SCALES.get(scale)?.to_string());
+        }
+        words.retain(|word| !word.is_empty());
+    }
+
+    if *magnitudes.start() < 0 {
+        words.push("point".to_string());
+        for magnitude in (*magnitudes.start()..0).rev() {
+            words.push(ENGLISH_ONES[usize::from(fixed_decimal.digit_at(magnitude))].to_string());
+        }
+    }
+
+    Some(words.join(" "))
+}
+
+fn spell_out_english_below_thousand(n: usize) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let mut words = Vec::new();
+    if hundreds > 0 {
+        words.push(format!("{} hundred", ENGLISH_ONES[hundreds]));
+    }
+    if rest >= 20 {
+        match rest % 10 {
+            0 => words.push(ENGLISH_TENS[rest / 10].to_string()),
+            ones => words.push(format!("{}-{}", ENGLISH_TENS[rest / 10], ENGLISH_ONES[ones])),
+        }
+    } else if rest > 0 {
+        words.push(ENGLISH_ONES[rest].to_string());
+    }
+    words.join(" ")
+}
+
+// CLDR's current tender for each region. ICU4X ships no region currency data, so
+// this covers the regions with baked locale data.
+fn region_currency(region: &str) -> Option<&'static str> {
+    Some(match region {
+        "AD" | "AT" | "BE" | "BG" | "CY" | "DE" | "EE" | "ES" | "FI" | "FR" | "GR" | "HR" | "IE" | "IT" | "LT"
+        | "LU" | "LV" | "MC" | "ME" | "MT" | "NL" | "PT" | "SI" | "SK" | "SM" | "VA" | "XK" => "EUR",
+        "US" | "EC" | "PR" | "SV" | "PA" => "USD",
+        "GB" => "GBP",
+        "CH" | "LI" => "CHF",
+        "SE" => "SEK",
+        "NO" => "NOK",
+        "DK" => "DKK",
+        "IS" => "ISK",
+        "PL" => "PLN",
+        "CZ" => "CZK",
+        "HU" => "HUF",
+        "RO" => "RON",
+        "RS" => "RSD",
+        "UA" => "UAH",
+        "RU" => "RUB",
+        "TR" => "TRY",
+        "IL" => "ILS",
+        "SA" => "SAR",
+        "AE" => "AED",
+        "EG" => "EGP",
+        "IR" => "IRR",
+        "ZA" => "ZAR",
+        "NG" => "NGN",
+        "KE" => "KES",
+        "CA" => "CAD",
+        "MX" => "MXN",
+        "BR" => "BRL",
+        "AR" => "ARS",
+        "CL" => "CLP",
+        "CO" => "COP",
+        "PE" => "PEN",
+        "JP" => "JPY",
+        "CN" => "CNY",
+        "TW" => "TWD",
+        "HK" => "HKD",
+        "KR" => "KRW",
+        "IN" => "INR",
+        "PK" => "PKR",
+        "BD" => "BDT",
+        "TH" => "THB",
+        "VN" => "VND",
+        "ID" => "IDR",
+        "MY" => "MYR",
+        "SG" => "SGD",
+        "PH" => "PHP",
+        "AU" => "AUD",
+        "NZ" => "NZD",
+        _ => return None,
+    })
+}
+
+// Replaces a `currency/$name` code with the value of parameter `name`
+fn resolve_currency_parameter<'a, P: ParameterSource + ?Sized>(
+    format_type: &'a NumberFormatType,
+    parameters: &P,
+) -> Result<Cow<'a, NumberFormatType>, FormatError> {
+    let NumberFormatType::Currency(code, display) = format_type else {
+        return Ok(Cow::Borrowed(format_type));
+    };
+    let Some(name) = code.as_deref().and_then(|code| code.strip_prefix('$')) else {
+        return Ok(Cow::Borrowed(format_type));
+    };
+
+    match parameters.get(name) {
+        Some(ParameterValue::String(value)) if value.len() == 3 && value.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Ok(Cow::Owned(NumberFormatType::Currency(Some(value.to_string()), *display)))
+        }
+        Some(value) => Err(type_mismatch(name, "3-letter currency code", value)),
+        None => Err(FormatError::MissingParameter(name.to_string())),
+    }
+}
+
+// Accepts `YYYY-MM-DD`, optionally followed by a `T...` time part which is ignored
+fn parse_iso_date(value: &str) -> Option<Date<Iso>> {
+    let date_part = value.split('T').next()?;
+    let mut fields = date_part.splitn(3, '-');
+    let year = fields.next()?.parse::<i32>().ok()?;
+    let month = fields.next()?.parse::<u8>().ok()?;
+    let day = fields.next()?.parse::<u8>().ok()?;
+    Date::try_new_iso_date(year, month, day).ok()
+}
+
+fn format_date(date: &Date<Iso>, style: DateTimeStyle, cache: &FormatterCache) -> Result<String, FormatError> {
+    cache.date(style)?.format_to_string(&date.to_any())
+        .map_err(|_| FormatError::Unsupported("date data".to_string()))
+}
+
+// Accepts `HH:MM` or `HH:MM:SS`, optionally preceded by a `...T` date part which is ignored
+fn parse_iso_time(value: &str) -> Option<Time> {
+    let time_part = value.rsplit('T').next()?;
+    let mut fields = time_part.splitn(3, ':');
+    let hour = fields.next()?.parse::<u8>().ok()?;
+    let minute = fields.next()?.parse::<u8>().ok()?;
+    let second = match fields.next() {
+        Some(second) => second.parse::<u8>().ok()?,
+        None => 0,
+    };
+    Time::try_new(hour, minute, second, 0).ok()
+}
+
+fn format_time(time: &Time, style: DateTimeStyle, cache: &FormatterCache) -> Result<String, FormatError> {
+    Ok(cache.time(style)?.format_to_string(time))
+}
+
+fn format_list(items: ListItems<'_>, style: ListStyle, cache: &FormatterCache) -> Result<String, FormatError> {
+    Ok(cache.list(style)?.format_to_string(items.iter()))
+}
+
+// What to render for an argument whose parameter is absent
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MissingBehavior {
+    // Fail with `FormatError::MissingParameter`
+    #[default]
+    Error,
+    // Render the argument's ICU source, e.g. `{name}`
+    KeepPlaceholder,
+    // Render nothing
+    Empty,
+}
+
+// How numbers are rounded when a currency or precision stem drops digits, named as
+// in ICU's `rounding-mode-*` skeleton stems
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RoundingMode {
+    // Ties go to the even neighbour, so `2.5` becomes `2` and `3.5` becomes `4`
+    #[default]
+    HalfEven,
+    // Ties go away from zero
+    HalfUp,
+    // Ties go toward zero
+    HalfDown,
+    // Toward positive infinity
+    Ceiling,
+    // Toward negative infinity
+    Floor,
+    // Away from zero
+    Up,
+    // Toward zero
+    Down,
+}
+
+impl RoundingMode {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "half-even" => Some(RoundingMode::HalfEven),
+            "half-up" => Some(RoundingMode::HalfUp),
+            "half-down" => Some(RoundingMode::HalfDown),
+            "ceiling" => Some(RoundingMode::Ceiling),
+            "floor" => Some(RoundingMode::Floor),
+            "up" => Some(RoundingMode::Up),
+            "down" => Some(RoundingMode::Down),
+            _ => None,
+        }
+    }
+
+    fn round(self, fixed_decimal: &mut FixedDecimal, position: i16) {
+        match self {
+            RoundingMode::HalfEven => fixed_decimal.half_even(position),
+            RoundingMode::HalfUp => fixed_decimal.half_expand(position),
+            RoundingMode::HalfDown => fixed_decimal.half_trunc(position),
+            RoundingMode::Ceiling => fixed_decimal.ceil(position),
+            RoundingMode::Floor => fixed_decimal.floor(position),
+            RoundingMode::Up => fixed_decimal.expand(position),
+            RoundingMode::Down => fixed_decimal.trunc(position),
+        }
+    }
+
+    fn round_to_increment(self, fixed_decimal: &mut FixedDecimal, position: i16, increment: RoundingIncrement) {
+        match self {
+            RoundingMode::HalfEven => fixed_decimal.half_even_to_increment(position, increment),
+            RoundingMode::HalfUp => fixed_decimal.half_expand_to_increment(position, increment),
+            RoundingMode::HalfDown => fixed_decimal.half_trunc_to_increment(position, increment),
+            RoundingMode::Ceiling => fixed_decimal.ceil_to_increment(position, increment),
+            RoundingMode::Floor => fixed_decimal.floor_to_increment(position, increment),
+            RoundingMode::Up => fixed_decimal.expand_to_increment(position, increment),
+            RoundingMode::Down => fixed_decimal.trunc_to_increment(position, increment),
+        }
+    }
+}
+
+// Applied to substituted values but never to template text, so markup written by
+// translators survives while markup in parameters is neutralized
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Escaper {
+    #[default]
+    None,
+    // Escapes `&`, `<`, `>`, `"` and `'` as HTML character references
+    Html,
+    // Appends the escaped form of the value to the output
+    Custom(fn(&str, &mut String)),
+}
+
+impl Escaper {
+    fn escape(self, value: &str, out: &mut Output<'_>) -> Result<(), FormatError> {
+        match self {
+            Escaper::None => out.push_str(value),
+            Escaper::Html => {
+                for c in value.chars() {
+                    match c {
+                        '&' => out.push_str("&amp;")?,
+                        '<' => out.push_str("&lt;")?,
+                        '>' => out.push_str("&gt;")?,
+                        '"' => out.push_str("&quot;")?,
+                        '\'' => out.push_str("&#39;")?,
+                        _ => out.push(c)?,
+                    }
+                }
+                Ok(())
+            }
+            Escaper::Custom(escape) => {
+                let mut escaped = String::new();
+                escape(value, &mut escaped);
+                out.push_str(&escaped)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub missing_parameter: MissingBehavior,
+    // Trims leading and trailing whitespace of the literal text in each selected case body
+    pub trim_case_whitespace: bool,
+    // Lets select values match selectors that differ only in case, e.g. `MALE` and `male`
+    pub case_insensitive_select: bool,
+    // In right-to-left locales, wraps each substituted value in U+2068/U+2069 isolates
+    // so that left-to-right values don't reorder the surrounding text
+    pub bidi_isolation: bool,
+    // The select case used when no selector matches the value, `other` by default
+    pub select_fallback: String,
+    // Turns `\n` in literal text into a newline and `\\` into a backslash, for templates
+    // kept in single-line storage; real newlines are copied either way
+    pub interpret_escapes: bool,
+    // Applied wherever formatting drops fraction digits, half-even by default; a
+    // `rounding-mode-*` skeleton stem overrides it for one argument
+    pub rounding_mode: RoundingMode,
+    // Escapes parameter, number, date, time and list substitutions
+    pub escape: Escaper,
+    // Rejects select values that only the fallback case would match, including the
+    // fallback's own name, so data that drifts from the authored cases fails loudly
+    pub strict_select: bool,
+    // Aborts formatting with `OutputTooLarge` once the output grows past this many
+    // bytes, for templates from untrusted authors; unlimited by default
+    pub max_output_bytes: Option<usize>,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            missing_parameter: MissingBehavior::default(),
+            trim_case_whitespace: false,
+            case_insensitive_select: false,
+            bidi_isolation: false,
+            select_fallback: "other".to_string(),
+            interpret_escapes: false,
+            rounding_mode: RoundingMode::default(),
+            escape: Escaper::default(),
+            strict_select: false,
+            max_output_bytes: None,
+        }
+    }
+}
+
+pub fn format_message<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+) -> Result<String, FormatError> {
+    format_message_with_options(message, parameters, locale, &FormatOptions::default())
+}
+
+pub fn format_message_with_options<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    format_message_cached(message, parameters, &FormatterCache::new(locale), options)
+}
+
+// Like `format_message`, trying each of `fallbacks` in order for ICU data or
+// locale-derived defaults that `locale` lacks, e.g. the currency of a region
+pub fn format_message_with_fallbacks<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+    fallbacks: &[Locale],
+) -> Result<String, FormatError> {
+    let cache = FormatterCache::with_fallbacks(locale, fallbacks);
+    format_message_cached(message, parameters, &cache, &FormatOptions::default())
+}
+
+pub(crate) fn format_message_cached<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let mut result = String::new();
+    format_submessage(message, &parameters, cache, options, None, false, &mut Output::new(&mut result, options))?;
+    Ok(result)
+}
+
+// Writes into any `fmt::Write` sink as the message is formatted, without building
+// the output as a `String` first. On error, everything formatted before the failing
+// element has already been written.
+pub fn format_message_to<P: ParameterSource, W: core::fmt::Write + ?Sized>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+    out: &mut W,
+) -> Result<(), FormatError> {
+    format_message_to_with_options(message, parameters, locale, &FormatOptions::default(), out)
+}
+
+// With `max_output_bytes`, nothing past the limit reaches `out`
+pub fn format_message_to_with_options<P: ParameterSource, W: core::fmt::Write + ?Sized>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+    options: &FormatOptions,
+    mut out: &mut W,
+) -> Result<(), FormatError> {
+    let cache = FormatterCache::new(locale);
+    format_submessage(message, &parameters, &cache, options, None, false, &mut Output::new(&mut out, options))
+}
+
+// Like `format_message`, but keeps going past failing arguments and reports every
+// error in the message, rendering each failed argument as its ICU source
+pub fn format_collecting_errors<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+) -> Result<String, Vec<FormatError>> {
+    let cache = FormatterCache::new(locale);
+    let options = FormatOptions::default();
+    let mut errors = Vec::new();
+    let mut result = String::new();
+    let mut out = Output::new(&mut result, &options);
+    out.errors = Some(&mut errors);
+    let outcome = format_submessage(message, &parameters, &cache, &options, None, false, &mut out);
+    match outcome {
+        Ok(()) if errors.is_empty() => Ok(result),
+        Ok(()) => Err(errors),
+        Err(err) => Err(vec![err]),
+    }
+}
+
+// Formats the case of a plural or select `element` written with `selector`, e.g.
+// `one`, `=0` or `male`, whatever case the parameters would select. Used to preview
+// each form of an argument: the parameters only provide the values inside the case
+// and the plural's `#`. A selector the element has no case for is `UnknownCase`.
+pub fn format_case<P: ParameterSource>(
+    element: &MessageElement,
+    selector: &str,
+    parameters: P,
+    locale: &Locale,
+) -> Result<String, FormatError> {
+    let cache = FormatterCache::new(locale);
+    let options = FormatOptions::default();
+    let unknown_case = |parameter: &str| FormatError::UnknownCase { parameter: parameter.to_string(), selector: selector.to_string() };
+
+    let mut result = String::new();
+    match element {
+        MessageElement::Plural(plural_expr) => {
+            let case = plural_expr
+                .cases
+                .iter()
+                .find(|case| case.selector.to_string() == selector)
+                .ok_or_else(|| unknown_case(&plural_expr.parameter))?;
+            let count = resolve_plural_count(plural_expr, &parameters)?;
+            let hash = format_plural_hash(plural_expr, count, &cache, &options)?;
+            write_case(&case.message, &parameters, &cache, &options, Some(&hash), &mut Output::new(&mut result, &options))?;
+        }
+        MessageElement::Select(select_expr) => {
+            let case = select_expr
+                .cases
+                .iter()
+                .find(|case| case.selector == selector)
+                .ok_or_else(|| unknown_case(&select_expr.parameter))?;
+            write_case(&case.message, &parameters, &cache, &options, None, &mut Output::new(&mut result, &options))?;
+        }
+        _ => return Err(FormatError::Unsupported(format!("format_case needs a plural or select argument, found {element}"))),
+    }
+    Ok(result)
+}
+
+// A piece of formatted output: template text, or the formatted value of the
+// argument named by `name`. Plural and select arguments contribute the parts of
+// their selected case, with `#` as an argument part of the plural's parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatPart {
+    Literal(String),
+    Argument { name: String, value: String },
+}
+
+pub fn format_to_parts<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+) -> Result<Vec<FormatPart>, FormatError> {
+    format_to_parts_with_options(message, parameters, locale, &FormatOptions::default())
+}
+
+pub fn format_to_parts_with_options<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+    options: &FormatOptions,
+) -> Result<Vec<FormatPart>, FormatError> {
+    let mut parts = Parts { parts: Vec::new(), len: 0, limit: options.max_output_bytes };
+    collect_parts(message, &parameters, &FormatterCache::new(locale), options, None, false, &mut parts)?;
+    Ok(parts.parts)
+}
+
+// The parts collected so far, whose combined size is checked against
+// `max_output_bytes` as each one is pushed
+struct Parts {
+    parts: Vec<FormatPart>,
+    len: usize,
+    limit: Option<usize>,
+}
+
+impl Parts {
+    fn push(&mut self, part: FormatPart) -> Result<(), FormatError> {
+        self.len += match &part {
+            FormatPart::Literal(text) => text.len(),
+            FormatPart::Argument { value, .. } => value.len(),
+        };
+        check_output_size(self.len, self.limit)?;
+        self.parts.push(part);
+        Ok(())
+    }
+}
+
+// `plural` is the parameter and formatted count of the nearest enclosing plural, for
+// `#`. `trim` is as for `format_submessage`; text trimmed away entirely leaves no part.
+fn collect_parts<P: ParameterSource + ?Sized>(
+    message: &Message,
+    parameters: &P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+    plural: Option<(&str, &str)>,
+    trim: bool,
+    parts: &mut Parts,
+) -> Result<(), FormatError> {
+    for (index, element) in message.elements.iter().enumerate() {
+        let missing = element.argument_name().is_some_and(|name| parameters.get(name).is_none());
+        let (case, count) = match element {
+            MessageElement::Plural(plural_expr) if !missing => {
+                let (case, hash) = resolve_plural_case(plural_expr, parameters, cache, options)?;
+                (case, Some((plural_expr.parameter.as_str(), hash)))
+            }
+            MessageElement::Select(select_expr) if !missing => (resolve_select_case(select_expr, parameters, options)?, None),
+            MessageElement::Shared(shared) => {
+                collect_parts(shared, parameters, cache, options, plural, false, parts)?;
+                continue;
+            }
+            MessageElement::Text(text) if trim => {
+                let mut value = String::new();
+                write_text(trim_case_text(text, index, message), options, &mut Output::new(&mut value, options))?;
+                if !value.is_empty() {
+                    parts.push(FormatPart::Literal(value))?;
+                }
+                continue;
+            }
+            _ => {
+                let mut value = String::new();
+                format_element(element, parameters, cache, options, plural.map(|(_, count)| count), &mut Output::new(&mut value, options))?;
+                let name = match element {
+                    MessageElement::PluralHash => plural.map(|(name, _)| name),
+                    _ => element.argument_name(),
+                };
+                match name {
+                    Some(name) => parts.push(FormatPart::Argument { name: name.to_string(), value })?,
+                    None => parts.push(FormatPart::Literal(value))?,
+                }
+                continue;
+            }
+        };
+
+        let plural = match &count {
+            Some((name, hash)) => Some((*name, hash.as_str())),
+            None => plural,
+        };
+        collect_parts(case.unshared(), parameters, cache, options, plural, options.trim_case_whitespace, parts)?;
+    }
+    Ok(())
+}
+
+// Trims `text`, the element at `index` of a case: its leading whitespace if it is
+// the first element, and its trailing whitespace if it is the last
+fn trim_case_text<'a>(text: &'a str, index: usize, case: &Message) -> &'a str {
+    let text = if index == 0 { text.trim_start() } else { text };
+    if index + 1 == case.elements.len() { text.trim_end() } else { text }
+}
+
+// Any other backslash is kept as written
+fn push_unescaped(out: &mut Output<'_>, text: &str) -> Result<(), FormatError> {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c)?;
+            continue;
+        }
+        match chars.peek() {
+            Some('n') => {
+                chars.next();
+                out.push('\n')?;
+            }
+            Some('\\') => {
+                chars.next();
+                out.push('\\')?;
+            }
+            _ => out.push('\\')?,
+        }
+    }
+    Ok(())
+}
+
+// The sink formatting writes into. It counts the bytes written so far, so that no
+// output past `max_output_bytes` ever reaches the sink, and holds the collector of
+// `format_collecting_errors`.
+struct Output<'a> {
+    sink: &'a mut dyn core::fmt::Write,
+    len: usize,
+    limit: Option<usize>,
+    errors: Option<&'a mut Vec<FormatError>>,
+}
+
+impl<'a> Output<'a> {
+    fn new(sink: &'a mut dyn core::fmt::Write, options: &FormatOptions) -> Self {
+        Output { sink, len: 0, limit: options.max_output_bytes, errors: None }
+    }
+
+    fn push_str(&mut self, text: &str) -> Result<(), FormatError> {
+        let len = self.len + text.len();
+        check_output_size(len, self.limit)?;
+        self.sink.write_str(text).map_err(|_| FormatError::Write)?;
+        self.len = len;
+        Ok(())
+    }
+
+    fn push(&mut self, c: char) -> Result<(), FormatError> {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+fn write_text(text: &str, options: &FormatOptions, out: &mut Output<'_>) -> Result<(), FormatError> {
+    if options.interpret_escapes {
+        push_unescaped(out, text)
+    } else {
+        out.push_str(text)
+    }
+}
+
+// Formats the selected case at the end of `out`. Only whitespace that comes from
+// the case's own leading or trailing text is trimmed, never whitespace inside a
+// substituted value.
+fn write_case<P: ParameterSource + ?Sized>(
+    case: &Message,
+    parameters: &P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+    plural_count: Option<&str>,
+    out: &mut Output<'_>,
+) -> Result<(), FormatError> {
+    format_submessage(case.unshared(), parameters, cache, options, plural_count, options.trim_case_whitespace, out)
+}
+
+// Writes each element straight into `out`, nested cases included. `plural_count` is
+// the formatted count of the nearest enclosing plural, used for `#`. `trim` drops the
+// leading whitespace of a first text element and the trailing whitespace of a last one.
+// With an errors collector in `out`, a failing element is recorded and rendered as its
+// ICU source instead of aborting the whole message; an element only writes once its
+// value is formatted, so a failed one has written nothing.
+fn format_submessage<P: ParameterSource + ?Sized>(
+    message: &Message,
+    parameters: &P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+    plural_count: Option<&str>,
+    trim: bool,
+    out: &mut Output<'_>,
+) -> Result<(), FormatError> {
+    for (index, element) in message.elements.iter().enumerate() {
+        let outcome = match element {
+            MessageElement::Text(text) if trim => write_text(trim_case_text(text, index, message), options, out),
+            _ => format_element(element, parameters, cache, options, plural_count, out),
+        };
+        if let Err(err) = outcome {
+            // Past the size limit or a failed sink, there is nowhere left to write
+            let Some(errors) = out.errors.as_deref_mut().filter(|_| !matches!(err, FormatError::OutputTooLarge { .. } | FormatError::Write)) else {
+                return Err(err);
+            };
+            errors.push(err);
+            out.push_str(&element.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_output_size(len: usize, limit: Option<usize>) -> Result<(), FormatError> {
+    match limit {
+        Some(limit) if len > limit => Err(FormatError::OutputTooLarge { limit }),
+        _ => Ok(()),
+    }
+}
+
+fn format_element<P: ParameterSource + ?Sized>(
+    element: &MessageElement,
+    parameters: &P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+    plural_count: Option<&str>,
+    out: &mut Output<'_>,
+) -> Result<(), FormatError> {
+    // `currency/$code` reads the currency from a second parameter
+    let currency_parameter = match element {
+        MessageElement::Number(number_expr) => match &number_expr.format_type {
+            NumberFormatType::Currency(Some(code), _) => code.strip_prefix('$'),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(name) = element.argument_name().into_iter().chain(currency_parameter).find(|name| parameters.get(name).is_none()) {
+        return match options.missing_parameter {
+            MissingBehavior::Error => Err(FormatError::MissingParameter(name.to_string())),
+            MissingBehavior::KeepPlaceholder => out.push_str(&element.to_string()),
+            MissingBehavior::Empty => Ok(()),
+        };
+    }
+
+    let value: Cow<'_, str> = match element {
+        MessageElement::Text(text) => return write_text(text, options, out),
+        MessageElement::Parameter(param_name) => match parameters.get(param_name) {
+            Some(ParameterValue::String(value)) => Cow::Borrowed(value),
+            Some(ParameterValue::Number(value)) => Cow::Owned(value.to_string()),
+            Some(ParameterValue::BigInteger(value)) => Cow::Owned(value.to_string()),
+            Some(ParameterValue::Float(value)) => {
+                let fixed_decimal = number_parameter(ParameterValue::Float(value), param_name)?;
+                Cow::Owned(format_number(fixed_decimal, &NumberFormatType::Number, options.rounding_mode, cache)?)
+            }
+            Some(ParameterValue::Decimal(value)) => {
+                Cow::Owned(format_number(value.clone(), &NumberFormatType::Number, options.rounding_mode, cache)?)
+            }
+            Some(ParameterValue::Bool(value)) => Cow::Borrowed(if value { "true" } else { "false" }),
+            Some(ParameterValue::List(items)) => Cow::Owned(format_list(items, ListStyle::And, cache)?),
+            Some(ParameterValue::Null) => Cow::Borrowed(""),
+            None => return Err(FormatError::MissingParameter(param_name.clone())),
+        },
+        MessageElement::Plural(plural_expr) => {
+            let (selected_message, hash) = resolve_plural_case(plural_expr, parameters, cache, options)?;
+            return write_case(selected_message, parameters, cache, options, Some(&hash), out);
+        }
+        MessageElement::Select(select_expr) => {
+            let selected_message = resolve_select_case(select_expr, parameters, options)?;
+            return write_case(selected_message, parameters, cache, options, plural_count, out);
+        }
+        MessageElement::Number(number_expr) => {
+            let Some(value) = parameters.get(&number_expr.parameter) else {
+                return Err(FormatError::MissingParameter(number_expr.parameter.clone()));
+            };
+            let number_value = number_parameter(value, &number_expr.parameter)?;
+            // Only whole numbers have an ordinal form; `3.7` is not `3rd`
+            if number_expr.format_type == NumberFormatType::Ordinal && number_value.nonzero_magnitude_end() < 0 {
+                return Err(type_mismatch(&number_expr.parameter, "integer", value));
+            }
+
+            let format_type = resolve_currency_parameter(&number_expr.format_type, parameters)?;
+            Cow::Owned(format_number(number_value, &format_type, options.rounding_mode, cache)?)
+        }
+        MessageElement::Date(date_expr) => {
+            let date = match parameters.get(&date_expr.parameter) {
+                Some(value @ ParameterValue::String(s)) => match parse_iso_date(s) {
+                    Some(date) => date,
+                    None => return Err(type_mismatch(&date_expr.parameter, "ISO date string", value)),
+                },
+                Some(value) => return Err(type_mismatch(&date_expr.parameter, "ISO date string", value)),
+                None => return Err(FormatError::MissingParameter(date_expr.parameter.clone())),
+            };
+
+            Cow::Owned(format_date(&date, date_expr.style, cache)?)
+        }
+        MessageElement::Time(time_expr) => {
+            let time = match parameters.get(&time_expr.parameter) {
+                Some(value @ ParameterValue::String(s)) => match parse_iso_time(s) {
+                    Some(time) => time,
+                    None => return Err(type_mismatch(&time_expr.parameter, "ISO time string", value)),
+                },
+                Some(value) => return Err(type_mismatch(&time_expr.parameter, "ISO time string", value)),
+                None => return Err(FormatError::MissingParameter(time_expr.parameter.clone())),
+            };
+
+            Cow::Owned(format_time(&time, time_expr.style, cache)?)
+        }
+        MessageElement::List(list_expr) => match parameters.get(&list_expr.parameter) {
+            Some(ParameterValue::List(items)) => Cow::Owned(format_list(items, list_expr.style, cache)?),
+            // A single string is a one-item list
+            Some(ParameterValue::String(s)) => Cow::Borrowed(s),
+            Some(value) => return Err(type_mismatch(&list_expr.parameter, "list", value)),
+            None => return Err(FormatError::MissingParameter(list_expr.parameter.clone())),
+        },
+        MessageElement::Shared(shared) => {
+            return format_submessage(shared, parameters, cache, options, plural_count, false, out);
+        }
+        MessageElement::PluralHash => match plural_count {
+            Some(hash) => Cow::Borrowed(hash),
+            // Outside any plural, `#` is template text
+            None => return out.push('#'),
+        },
+    };
+
+    if !is_substitution(element) {
+        return out.push_str(&value);
+    }
+    let isolate = options.bidi_isolation && cache.is_right_to_left();
+    if isolate {
+        out.push(FIRST_STRONG_ISOLATE)?;
+    }
+    options.escape.escape(&value, out)?;
+    if isolate {
+        out.push(POP_DIRECTIONAL_ISOLATE)?;
+    }
+    Ok(())
+}
+
+const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+fn resolve_plural_case<'a, P: ParameterSource + ?Sized>(
+    plural_expr: &'a PluralExpression,
+    parameters: &P,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+) -> Result<(&'a Message, String), FormatError> {
+    let count = resolve_plural_count(plural_expr, parameters)?;
+    let rules = cache.plural_rules()?;
+
+    let selected_message = select_plural_case(plural_expr, &count, rules)
+        .ok_or_else(|| FormatError::NoMatchingCase(plural_expr.parameter.clone()))?;
+    let hash = format_plural_hash(plural_expr, count, cache, options)?;
+    Ok((selected_message, hash))
+}
+
+fn resolve_plural_count<P: ParameterSource + ?Sized>(
+    plural_expr: &PluralExpression,
+    parameters: &P,
+) -> Result<FixedDecimal, FormatError> {
+    let count = match parameters.get(&plural_expr.parameter) {
+        // A null count has no plural category, so it's an error rather than `other`
+        Some(value) => number_parameter(value, &plural_expr.parameter)?,
+        None => {
+            return Err(FormatError::MissingParameter(plural_expr.parameter.clone()));
+        }
+    };
+    Ok(count)
+}
+
+// `#` is grouped like a standalone `{n, number}` unless the plural has its own skeleton
+fn format_plural_hash(
+    plural_expr: &PluralExpression,
+    count: FixedDecimal,
+    cache: &FormatterCache,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    match &plural_expr.hash_skeleton {
+        Some(skeleton) => format_skeleton(count, skeleton, options.rounding_mode, cache),
+        None => format_decimal(&count, GroupingStrategy::Auto, cache),
+    }
+}
+
+fn resolve_select_case<'a, P: ParameterSource + ?Sized>(
+    select_expr: &'a SelectExpression,
+    parameters: &P,
+    options: &FormatOptions,
+) -> Result<&'a Message, FormatError> {
+    let value = match parameters.get(&select_expr.parameter) {
+        Some(ParameterValue::Null) => "null",
+        Some(value) => match value.as_str() {
+            Some(s) => s,
+            None => return Err(type_mismatch(&select_expr.parameter, "string", value)),
+        },
+        None => return Err(FormatError::MissingParameter(select_expr.parameter.clone())),
+    };
+
+    if options.strict_select {
+        return match matching_select_case(select_expr, value, options.case_insensitive_select) {
+            Some(case) if case.selector != options.select_fallback => Ok(&case.message),
+            _ => Err(FormatError::UnmatchedSelect { parameter: select_expr.parameter.clone(), value: value.to_string() }),
+        };
+    }
+
+    select_case(select_expr, value, options.case_insensitive_select, &options.select_fallback)
+        .ok_or_else(|| FormatError::NoMatchingCase(select_expr.parameter.clone()))
+}
+
+// Elements whose output is a caller-supplied value rather than template text
+fn is_substitution(element: &MessageElement) -> bool {
+    matches!(
+        element,
+        MessageElement::Parameter(_)
+            | MessageElement::Number(_)
+            | MessageElement::Date(_)
+            | MessageElement::Time(_)
+            | MessageElement::List(_)
+            | MessageElement::PluralHash
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params;
+    use icu::locid::locale;
+    use crate::types::{MessageElement, PluralCase, PluralExpression, PluralSelector, SelectExpression, NumberExpression, NumberFormatType};
+
+    #[test]
+    fn test_format_text_only() {
+        let message = Message::new(vec![MessageElement::Text("Hello world".to_string())]);
+        let params = params!();
+
+        let result = format_message(&message, params, &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Hello world");
+    }
+
+    #[test]
+    fn test_format_single_parameter() {
+        let message = Message::new(vec![
+            MessageElement::Text("Hello ".to_string()),
+            MessageElement::Parameter("name".to_string()),
+        ]);
+
+        let result = format_message(&message, params!("name" => "Alice"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Hello Alice");
+    }
+
+    #[test]
+    fn test_format_multiple_parameters() {
+        let message = Message::new(vec![
+            MessageElement::Text("Hello ".to_string()),
+            MessageElement::Parameter("firstName".to_string()),
+            MessageElement::Text(" ".to_string()),
+            MessageElement::Parameter("lastName".to_string()),
+            MessageElement::Text("!".to_string()),
+        ]);
+        let result = format_message(&message, params!(
+            "firstName" => "Alice",
+            "lastName" => "Johnson"
+        ), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Hello Alice Johnson!");
+    }
+
+    #[test]
+    fn test_format_missing_parameter() {
+        let message = Message::new(vec![
+            MessageElement::Text("Hello ".to_string()),
+            MessageElement::Parameter("name".to_string()),
+        ]);
+        let params = params!();
+
+        let result = format_message(&message, params, &locale!("en-US"));
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            FormatError::MissingParameter("name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_plural_one() {
+        let plural_expr = PluralExpression {
+            parameter: "count".to_string(),
+            hash_skeleton: None,
+            cases: vec![
+                PluralCase {
+                    selector: PluralSelector::One,
+                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Other,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" items".to_string()),
+                    ]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
+        let result = format_message(&message, params!("count" => 1), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "1 item");
+    }
+
+    #[test]
+    fn test_format_plural_other() {
+        let plural_expr = PluralExpression {
+            parameter: "count".to_string(),
+            hash_skeleton: None,
+            cases: vec![
+                PluralCase {
+                    selector: PluralSelector::One,
+                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Other,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" items".to_string()),
+                    ]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
+        let result = format_message(&message, params!("count" => 5), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "5 items");
+    }
+
+    #[test]
+    fn test_format_plural_with_context() {
+        let plural_expr = PluralExpression {
+            parameter: "count".to_string(),
+            hash_skeleton: None,
+            cases: vec![
+                PluralCase {
+                    selector: PluralSelector::One,
+                    message: Message::new(vec![MessageElement::Text("1 item".to_string())]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Other,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" items".to_string()),
+                    ]),
+                },
+            ],
+        };
+        let message = Message::new(vec![
+            MessageElement::Text("You have ".to_string()),
+            MessageElement::Plural(plural_expr),
+            MessageElement::Text(" in your cart.".to_string()),
+        ]);
+        let result = format_message(&message, params!("count" => 3), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "You have 3 items in your cart.");
+    }
+
+    #[test]
+    fn test_format_select_male() {
+        let select_expr = SelectExpression {
+            parameter: "gender".to_string(),
+            cases: vec![
+                SelectCase {
+                    selector: "male".to_string(),
+                    message: Message::new(vec![MessageElement::Text("He likes this.".to_string())]),
+                },
+                SelectCase {
+                    selector: "female".to_string(),
+                    message: Message::new(vec![MessageElement::Text("She likes this.".to_string())]),
+                },
+                SelectCase {
+                    selector: "other".to_string(),
+                    message: Message::new(vec![MessageElement::Text("They like this.".to_string())]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Select(select_expr)]);
+
+        let result = format_message(&message, params!("gender" => "male"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "He likes this.");
+    }
+
+    #[test]
+    fn test_format_select_female() {
+        let select_expr = SelectExpression {
+            parameter: "gender".to_string(),
+            cases: vec![
+                SelectCase {
+                    selector: "male".to_string(),
+                    message: Message::new(vec![MessageElement::Text("He likes this.".to_string())]),
+                },
+                SelectCase {
+                    selector: "female".to_string(),
+                    message: Message::new(vec![MessageElement::Text("She likes this.".to_string())]),
+                },
+                SelectCase {
+                    selector: "other".to_string(),
+                    message: Message::new(vec![MessageElement::Text("They like this.".to_string())]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Select(select_expr)]);
+
+        let result = format_message(&message, params!("gender" => "female"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "She likes this.");
     }
 
     #[test]
@@ -424,101 +2276,1226 @@ mod tests {
         };
         let message = Message::new(vec![MessageElement::Select(select_expr)]);
 
-        let result = format_message(&message, params!("gender" => "nonbinary"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "They like this.");
+        let result = format_message(&message, params!("gender" => "nonbinary"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "They like this.");
+    }
+
+    #[test]
+    fn test_format_html_escaping() {
+        let message = crate::parser::parse_message_complete("<b>{name}</b> wrote {n, plural, other{# <i>{title}</i>}}").unwrap();
+        let options = FormatOptions { escape: Escaper::Html, ..FormatOptions::default() };
+        let result = format_message_with_options(
+            &message,
+            params!("name" => "<b>Tom & \"Jerry\"</b>", "n" => 2, "title" => "it's <script>"),
+            &locale!("en"),
+            &options,
+        );
+        assert_eq!(
+            result.unwrap(),
+            "<b>&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;</b> wrote 2 <i>it&#39;s &lt;script&gt;</i>"
+        );
+
+        fn shout(value: &str, result: &mut String) {
+            result.push_str(&value.to_uppercase());
+        }
+        let options = FormatOptions { escape: Escaper::Custom(shout), ..FormatOptions::default() };
+        let result = format_message_with_options(&message, params!("name" => "tom", "n" => 1, "title" => "hi"), &locale!("en"), &options);
+        assert_eq!(result.unwrap(), "<b>TOM</b> wrote 1 <i>HI</i>");
+    }
+
+    #[test]
+    fn test_format_to_parts() {
+        let message = crate::parser::parse_message_complete(
+            "Hi {name}, {count, plural, one{# new {kind}} other{# new messages}}. {total, number}",
+        )
+        .unwrap();
+        let parts = format_to_parts(&message, params!("name" => "Ann", "count" => 1, "kind" => "note", "total" => 1234), &locale!("en")).unwrap();
+        let argument = |name: &str, value: &str| FormatPart::Argument { name: name.to_string(), value: value.to_string() };
+        let literal = |text: &str| FormatPart::Literal(text.to_string());
+        assert_eq!(
+            parts,
+            [
+                literal("Hi "),
+                argument("name", "Ann"),
+                literal(", "),
+                argument("count", "1"),
+                literal(" new "),
+                argument("kind", "note"),
+                literal(". "),
+                argument("total", "1,234"),
+            ]
+        );
+
+        let message = crate::parser::parse_message_complete("{g, select, other{ {n, plural, other{#} } }}").unwrap();
+        let options = FormatOptions { trim_case_whitespace: true, ..FormatOptions::default() };
+        let parts = format_to_parts_with_options(&message, params!("g" => "x", "n" => 3), &locale!("en"), &options).unwrap();
+        assert_eq!(parts, [argument("n", "3")]);
+        let parts = format_to_parts(&message, params!("g" => "x", "n" => 3), &locale!("en")).unwrap();
+        assert_eq!(parts, [literal(" "), argument("n", "3"), literal(" ")]);
+
+        assert_eq!(
+            format_to_parts(&message, params!("g" => "x"), &locale!("en")),
+            Err(FormatError::MissingParameter("n".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_format_empty_case_bodies() {
+        let message = crate::parser::parse_message_complete("{count, plural, =0{} other{# items}}").unwrap();
+        assert_eq!(format_message(&message, params!("count" => 0), &locale!("en")).unwrap(), "");
+        assert_eq!(format_message(&message, params!("count" => 3), &locale!("en")).unwrap(), "3 items");
+
+        let message = crate::parser::parse_message_complete("Done{g, select, quiet{} other{!}}").unwrap();
+        assert_eq!(format_message(&message, params!("g" => "quiet"), &locale!("en")).unwrap(), "Done");
+        let options = FormatOptions { trim_case_whitespace: true, ..FormatOptions::default() };
+        let result = format_message_with_options(&message, params!("g" => "quiet"), &locale!("en"), &options);
+        assert_eq!(result.unwrap(), "Done");
+    }
+
+    #[test]
+    fn test_format_gender_argument() {
+        let message = crate::parser::parse_message_complete("{g, gender, masculine{He} feminine{She} other{They}} replied").unwrap();
+        let format = |g: &str| format_message(&message, params!("g" => g), &locale!("en-US")).unwrap();
+        assert_eq!(format("feminine"), "She replied");
+        assert_eq!(format("masculine"), "He replied");
+        assert_eq!(format("neuter"), "They replied");
+    }
+
+    #[test]
+    fn test_format_number_basic() {
+        let number_expr = NumberExpression {
+            parameter: "count".to_string(),
+            format_type: NumberFormatType::Number,
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("count" => 42), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "42");
+    }
+
+    #[test]
+    fn test_format_number_decimal() {
+        let number_expr = NumberExpression {
+            parameter: "price".to_string(),
+            format_type: NumberFormatType::Number,
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("price" => "19.99"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "19.99");
+    }
+
+    #[test]
+    fn test_format_number_integer() {
+        let number_expr = NumberExpression {
+            parameter: "count".to_string(),
+            format_type: NumberFormatType::Integer,
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("count" => "19.99"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "19");
+    }
+
+    #[test]
+    fn test_format_number_percent() {
+        let number_expr = NumberExpression {
+            parameter: "ratio".to_string(),
+            format_type: NumberFormatType::Percent,
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("ratio" => "0.75"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "75%");
+    }
+
+    #[test]
+    fn test_parameter_accessors_match_formatting() {
+        let number = crate::parser::parse_message_complete("{n, number}").unwrap();
+        let plural = crate::parser::parse_message_complete("{n, plural, other{#}}").unwrap();
+        let strings = ["1e3", "17", "-2.50", ".5", "9007199254740993", "1.5e-2", "NaN", "inf", "abc", "1e32000"];
+        let values = strings.map(ParameterValue::String).into_iter().chain([
+            ParameterValue::Float(2.5),
+            ParameterValue::Float(f64::NAN),
+            ParameterValue::Float(f64::INFINITY),
+            ParameterValue::BigInteger(i128::MAX),
+            ParameterValue::Bool(true),
+        ]);
+
+        for value in values {
+            let format = |message: &Message| format_message(message, crate::types::Parameters::from_slice(&[("n", value)]), &locale!("en"));
+            let accepted = value.as_decimal().is_some();
+            assert_eq!(format(&number).is_ok(), accepted, "{value:?}");
+            assert_eq!(format(&plural).is_ok(), accepted, "{value:?}");
+            assert_eq!(value.as_f64().is_some(), accepted, "{value:?}");
+            if let Some(whole) = value.as_i64() {
+                assert_eq!(format(&number), format_message(&number, params!("n" => whole), &locale!("en")), "{value:?}");
+            }
+        }
+        assert_eq!(ParameterValue::String("1e3").as_i64(), Some(1000));
+    }
+
+    #[test]
+    fn test_format_number_from_scientific_string() {
+        let message = crate::parser::parse_message_complete("{n, number}").unwrap();
+        let format = |n: &str| format_message(&message, params!("n" => n), &locale!("en"));
+
+        assert_eq!(format("1e3").unwrap(), "1,000");
+        assert_eq!(format("1.5e-2").unwrap(), "0.015");
+        assert_eq!(format("2.5E+2").unwrap(), "250");
+        // Past 2^53 an `f64` would round this to ...992
+        assert_eq!(format("9007199254740993").unwrap(), "9,007,199,254,740,993");
+        assert_eq!(format("1.234567890123456789e20").unwrap(), "123,456,789,012,345,678,900");
+        assert_eq!(format(".5").unwrap(), "0.5");
+
+        assert!(matches!(format("1e99999"), Err(FormatError::InvalidParameterType { found: "string", .. })));
+        assert!(matches!(format("1e32000"), Err(FormatError::InvalidParameterType { found: "string", .. })));
+        assert!(matches!(format("1e-1001"), Err(FormatError::InvalidParameterType { found: "string", .. })));
+        assert_eq!(format("1e1000").unwrap().len(), 1334);
+        assert!(matches!(format("abc"), Err(FormatError::InvalidParameterType { found: "string", .. })));
+
+        let plural = crate::parser::parse_message_complete("{n, plural, one{# item} other{# items}}").unwrap();
+        assert_eq!(format_message(&plural, params!("n" => "1e3"), &locale!("en")).unwrap(), "1,000 items");
+    }
+
+    #[test]
+    fn test_format_number_currency_usd() {
+        let number_expr = NumberExpression {
+            parameter: "price".to_string(),
+            format_type: NumberFormatType::Currency(Some("USD".to_string()), CurrencyDisplay::Symbol),
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("price" => "19.99"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "$19.99");
+    }
+
+    #[test]
+    fn test_format_number_currency_eur() {
+        let number_expr = NumberExpression {
+            parameter: "price".to_string(),
+            format_type: NumberFormatType::Currency(Some("EUR".to_string()), CurrencyDisplay::Symbol),
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("price" => 25), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "€25.00");
+    }
+
+    #[test]
+    fn test_format_number_currency_any_valid_code() {
+        let number_expr = NumberExpression {
+            parameter: "price".to_string(),
+            format_type: NumberFormatType::Currency(Some("SEK".to_string()), CurrencyDisplay::Symbol),
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+
+        let result = format_message(&message, params!("price" => 100), &locale!("en-US"));
+        assert!(result.is_ok());
+        // ICU4X should handle SEK (Swedish Krona) even though we didn't hardcode it
+        let formatted = result.unwrap();
+        assert!(formatted.contains("100") || formatted.contains("SEK"));
+    }
+
+    #[test]
+    fn test_format_plural_polish_few_and_many() {
+        let plural_expr = PluralExpression {
+            parameter: "count".to_string(),
+            hash_skeleton: None,
+            cases: vec![
+                PluralCase {
+                    selector: PluralSelector::One,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" plik".to_string()),
+                    ]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Few,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" pliki".to_string()),
+                    ]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Many,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" plików".to_string()),
+                    ]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Other,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" pliku".to_string()),
+                    ]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
+
+        let result = format_message(&message, params!("count" => 2), &locale!("pl"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "2 pliki");
+
+        let result = format_message(&message, params!("count" => 5), &locale!("pl"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "5 plików");
+    }
+
+    #[test]
+    fn test_format_null_parameter() {
+        let nickname: Option<&str> = None;
+        let message = crate::parser::parse_message_complete("Hi {name}{nick, select, null{} other{ ({nick})}}").unwrap();
+        let result = format_message(&message, params!("name" => "Ada", "nick" => nickname), &locale!("en"));
+        assert_eq!(result.unwrap(), "Hi Ada");
+        let result = format_message(&message, params!("name" => "Ada", "nick" => Some("A")), &locale!("en"));
+        assert_eq!(result.unwrap(), "Hi Ada (A)");
+
+        // Without a `null` case, select falls back to `other`, where the value is empty
+        let message = crate::parser::parse_message_complete("[{g, select, male{he} other{{g}}}]").unwrap();
+        let result = format_message(&message, params!("g" => nickname), &locale!("en"));
+        assert_eq!(result.unwrap(), "[]");
+
+        let message = crate::parser::parse_message_complete("{n, plural, one{# item} other{# items}}").unwrap();
+        let result = format_message(&message, params!("n" => None::<i64>), &locale!("en"));
+        let error = result.unwrap_err();
+        assert_eq!(error, FormatError::InvalidParameterType { parameter: "n".to_string(), expected: "number", found: "null" });
+        assert_eq!(error.to_string(), "parameter `n`: expected number, found null");
+
+        let message = crate::parser::parse_message_complete("{n, number}").unwrap();
+        let result = format_message(&message, params!("n" => None::<f64>), &locale!("en"));
+        assert!(matches!(result, Err(FormatError::InvalidParameterType { found: "null", .. })));
+    }
+
+    #[test]
+    fn test_format_plural_french_many_for_millions() {
+        let message = crate::parser::parse_message_complete("{n, plural, one{# an} many{# de ans} other{# ans}}").unwrap();
+        let format = |n: i64| format_message(&message, params!("n" => n), &locale!("fr")).unwrap();
+        assert_eq!(format(1), "1 an");
+        assert_eq!(format(1000), "1\u{202f}000 ans");
+        assert_eq!(format(1000000), "1\u{202f}000\u{202f}000 de ans");
+        assert_eq!(format(3000000), "3\u{202f}000\u{202f}000 de ans");
+        assert_eq!(format(1000001), "1\u{202f}000\u{202f}001 ans");
+
+        // `many` only applies to whole millions without visible fraction digits
+        let result = format_message(&message, params!("n" => 1000000.5), &locale!("fr"));
+        assert_eq!(result.unwrap(), "1\u{202f}000\u{202f}000,5 ans");
+
+        let message = crate::parser::parse_message_complete("{n, plural, one{# an} other{# ans}}").unwrap();
+        let result = format_message(&message, params!("n" => 1000000), &locale!("fr"));
+        assert_eq!(result.unwrap(), "1\u{202f}000\u{202f}000 ans");
+    }
+
+    #[test]
+    fn test_format_plural_hash_skeleton() {
+        let message = crate::parser::parse_message_complete("{n, plural, ::compact-short one{# view} other{# views}}").unwrap();
+        let format = |n: i64| format_message(&message, params!("n" => n), &locale!("en")).unwrap();
+        assert_eq!(format(1200), "1.2K views");
+        assert_eq!(format(1), "1 view");
+        // The category still comes from the count itself
+        assert_eq!(format(1000), "1K views");
+
+        let message = crate::parser::parse_message_complete(
+            "{n, plural, ::.00 other{# in {g, select, other{#}}}} {n, plural, other{#}}",
+        )
+        .unwrap();
+        let result = format_message(&message, params!("n" => 1234, "g" => "x"), &locale!("de"));
+        assert_eq!(result.unwrap(), "1.234,00 in 1.234,00 1.234");
+        let parts = format_to_parts(&message, params!("n" => 5, "g" => "x"), &locale!("en")).unwrap();
+        assert_eq!(parts[0], FormatPart::Argument { name: "n".to_string(), value: "5.00".to_string() });
+
+        let message = crate::parser::parse_message_complete("{n, plural, ::sparkly other{#}}").unwrap();
+        let result = format_message(&message, params!("n" => 5), &locale!("en"));
+        assert_eq!(result, Err(FormatError::Unsupported("Unknown number skeleton token: sparkly".to_string())));
+    }
+
+    #[test]
+    fn test_format_plural_range() {
+        let message = crate::parser::parse_message_complete("{n, plural, =3{three} 2..4{# is a few} one{# item} other{# items}}").unwrap();
+        let format = |n: i64| format_message(&message, params!("n" => n), &locale!("en")).unwrap();
+        assert_eq!(format(2), "2 is a few");
+        assert_eq!(format(4), "4 is a few");
+        assert_eq!(format(3), "three");
+        assert_eq!(format(5), "5 items");
+        assert_eq!(format(1), "1 item");
+    }
+
+    #[test]
+    fn test_format_plural_exact_wins_over_category() {
+        let plural_expr = PluralExpression {
+            parameter: "count".to_string(),
+            hash_skeleton: None,
+            cases: vec![
+                PluralCase {
+                    selector: PluralSelector::Exact(2),
+                    message: Message::new(vec![MessageElement::Text("a pair".to_string())]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Few,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" pliki".to_string()),
+                    ]),
+                },
+                PluralCase {
+                    selector: PluralSelector::Other,
+                    message: Message::new(vec![
+                        MessageElement::PluralHash,
+                        MessageElement::Text(" pliku".to_string()),
+                    ]),
+                },
+            ],
+        };
+        let message = Message::new(vec![MessageElement::Plural(plural_expr)]);
+
+        let result = format_message(&message, params!("count" => 2), &locale!("pl"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "a pair");
+    }
+
+    fn format_with_skeleton(skeleton: &str, value: &str) -> Result<String, FormatError> {
+        let number_expr = NumberExpression {
+            parameter: "value".to_string(),
+            format_type: NumberFormatType::Skeleton(skeleton.to_string()),
+        };
+        let message = Message::new(vec![MessageElement::Number(number_expr)]);
+        format_message(&message, params!("value" => value), &locale!("en-US"))
+    }
+
+    #[test]
+    fn test_format_skeleton_fraction_digits() {
+        assert_eq!(format_with_skeleton(".00", "19.5").unwrap(), "19.50");
+        assert_eq!(format_with_skeleton(".0#", "19.567").unwrap(), "19.57");
+        assert_eq!(format_with_skeleton(".0#", "19").unwrap(), "19.0");
+        assert_eq!(format_with_skeleton(".00/w", "19").unwrap(), "19");
+        assert_eq!(format_with_skeleton(".00/w", "19.5").unwrap(), "19.50");
+    }
+
+    #[test]
+    fn test_format_skeleton_significant_digits() {
+        assert_eq!(format_with_skeleton("@@@", "0.04567").unwrap(), "0.0457");
+        assert_eq!(format_with_skeleton("@@@", "123456").unwrap(), "123,000");
+        assert_eq!(format_with_skeleton("@@@ group-off", "123456").unwrap(), "123000");
+        assert_eq!(format_with_skeleton("@@@", "1.5").unwrap(), "1.50");
+        assert_eq!(format_with_skeleton("@@@", "0").unwrap(), "0.00");
+        assert_eq!(format_with_skeleton("@@@", "999.7").unwrap(), "1,000");
+        assert_eq!(format_with_skeleton("@#", "1.5").unwrap(), "1.5");
+        assert_eq!(format_with_skeleton("@#", "1.04").unwrap(), "1");
+        assert_eq!(format_with_skeleton("@@+", "3.14159").unwrap(), "3.14159");
+        assert_eq!(format_with_skeleton("@@+", "3").unwrap(), "3.0");
+        assert_eq!(format_with_skeleton("@@@ rounding-mode-ceiling", "1.231").unwrap(), "1.24");
+        assert_eq!(format_with_skeleton("scientific @@@", "123456").unwrap(), "1.23E5");
+        assert_eq!(format_with_skeleton("@@ currency/USD", "1234").unwrap(), "$1,200");
+
+        let message = crate::parser::parse_message_complete("{n, number, ::@@@}").unwrap();
+        assert_eq!(format_message(&message, params!("n" => 0.04567), &locale!("de")).unwrap(), "0,0457");
+
+        assert!(format_with_skeleton("#@", "1").is_err());
+        assert!(format_with_skeleton("@#+", "1").is_err());
+    }
+
+    #[test]
+    fn test_format_skeleton_precision_increment() {
+        assert_eq!(format_with_skeleton("precision-increment/0.05", "1.23").unwrap(), "1.25");
+        assert_eq!(format_with_skeleton("precision-increment/0.05", "1.22").unwrap(), "1.20");
+        assert_eq!(format_with_skeleton("precision-increment/0.05", "2").unwrap(), "2.00");
+        assert_eq!(format_with_skeleton("precision-increment/0.25", "1.13").unwrap(), "1.25");
+        assert_eq!(format_with_skeleton("precision-increment/0.5", "-3.7").unwrap(), "-3.5");
+        assert_eq!(format_with_skeleton("precision-increment/50", "1234").unwrap(), "1,250");
+        assert_eq!(format_with_skeleton("precision-increment/0.05 rounding-mode-floor", "1.24").unwrap(), "1.20");
+        assert_eq!(format_with_skeleton("currency/USD precision-increment/0.05", "9.99").unwrap(), "$10.00");
+
+        assert!(format_with_skeleton("precision-increment/0.3", "1").is_err());
+        assert!(format_with_skeleton("precision-increment/0", "1").is_err());
+        assert!(format_with_skeleton("precision-increment/x", "1").is_err());
+    }
+
+    #[test]
+    fn test_format_rounding_mode() {
+        let message = crate::parser::parse_message_complete("{a, number, :: .} {b, number, currency/JPY}").unwrap();
+        let format = |rounding_mode| {
+            let options = FormatOptions { rounding_mode, ..FormatOptions::default() };
+            format_message_with_options(&message, params!("a" => 2.5, "b" => 100.5), &locale!("en-US"), &options).unwrap()
+        };
+        assert_eq!(format(RoundingMode::HalfEven), "2 ¥100");
+        assert_eq!(format(RoundingMode::HalfUp), "3 ¥101");
+        assert_eq!(format(RoundingMode::Down), "2 ¥100");
+        assert_eq!(format(RoundingMode::Ceiling), "3 ¥101");
+
+        // A skeleton stem overrides the option for its argument only
+        assert_eq!(format_with_skeleton(". rounding-mode-half-up", "2.5").unwrap(), "3");
+        assert_eq!(format_with_skeleton(".0 rounding-mode-floor", "-1.25").unwrap(), "-1.3");
+        assert_eq!(format_with_skeleton(".0 rounding-mode-half-down", "1.25").unwrap(), "1.2");
+    }
+
+    #[test]
+    fn test_format_locale_numbering_system() {
+        let message = crate::parser::parse_message_complete("{n, number} {n, plural, other{#}} {p, number, percent}").unwrap();
+        let format = |locale: &Locale| format_message(&message, params!("n" => 123, "p" => 0.5), locale).unwrap();
+        assert_eq!(format(&locale!("ar-EG")), "١٢٣ ١٢٣ ٥٠\u{200e}%\u{200e}");
+        // CLDR defaults plain `ar` to Latin digits; the `nu` keyword selects Arabic-Indic
+        assert_eq!(format(&locale!("ar")), "123 123 50\u{200e}%\u{200e}");
+        assert_eq!(format(&"ar-u-nu-arab".parse().unwrap()), "١٢٣ ١٢٣ ٥٠\u{200e}%\u{200e}");
+        assert_eq!(format(&locale!("fa")), "۱۲۳ ۱۲۳ ۵۰%");
+    }
+
+    #[test]
+    fn test_format_skeleton_locale_override() {
+        let message = crate::parser::parse_message_complete(
+            "{a, number} {b, number, ::locale/en-US .00} {c, number} {d, number, :: locale/en-IN currency/INR}",
+        )
+        .unwrap();
+        let result = format_message(&message, params!("a" => 1234.5, "b" => 1234.5, "c" => 1234.5, "d" => 1234567), &locale!("de-DE"));
+        // Only the overridden arguments change; the one between them keeps the message locale
+        assert_eq!(result.unwrap(), "1.234,5 1,234.50 1.234,5 ₹12,34,567.00");
+
+        assert_eq!(format_with_skeleton("locale/ar-EG", "12").unwrap(), "١٢");
+        assert_eq!(
+            format_with_skeleton("locale/12", "1").unwrap_err(),
+            FormatError::Unsupported("Invalid locale: 12".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_skeleton_numbering_system() {
+        let message = crate::parser::parse_message_complete("{n, number, :: numbering-system/arab .00}").unwrap();
+        let format = |locale: &Locale| format_message(&message, params!("n" => 1234.5), locale).unwrap();
+        // Arabic locales use the system's own separators
+        assert_eq!(format(&locale!("ar")), "١٬٢٣٤٫٥٠");
+        // Locales without data for it keep their separators
+        assert_eq!(format(&locale!("en-US")), "١,٢٣٤.٥٠");
+        assert_eq!(format_with_skeleton("numbering-system/latn", "42").unwrap(), "42");
+        assert_eq!(format_with_skeleton("numbering-system/arab pad/3", "7").unwrap(), "  \u{667}");
+        assert_eq!(format_with_skeleton("numbering-system/deva percent", "0.5").unwrap(), "५०%");
+        assert_eq!(
+            format_with_skeleton("numbering-system/klingon", "1").unwrap_err(),
+            FormatError::Unsupported("Unknown numbering system: klingon".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_skeleton_group_off() {
+        assert_eq!(format_with_skeleton("group-off", "1234567").unwrap(), "1234567");
+    }
+
+    #[test]
+    fn test_format_skeleton_compact_short() {
+        assert_eq!(format_with_skeleton("compact-short", "1234").unwrap(), "1.2K");
+        assert_eq!(format_with_skeleton("compact-short", "3400000").unwrap(), "3.4M");
+        assert_eq!(format_with_skeleton("compact-long", "3400000").unwrap(), "3.4 million");
     }
 
     #[test]
-    fn test_format_number_basic() {
+    fn test_format_compact_below_thousand() {
+        assert_eq!(format_with_skeleton("compact-short", "999").unwrap(), "999");
+        assert_eq!(format_with_skeleton("compact-short", "12.345").unwrap(), "12.345");
+    }
+
+    #[test]
+    fn test_format_skeleton_currency() {
+        assert_eq!(format_with_skeleton("currency/USD .00", "25").unwrap(), "$25.00");
+    }
+
+    #[test]
+    fn test_format_skeleton_unknown_token() {
+        assert_eq!(
+            format_with_skeleton(".00 sparkle", "1").unwrap_err(),
+            FormatError::Unsupported("Unknown number skeleton token: sparkle".to_string())
+        );
+    }
+
+    fn format_percent_in(value: &str, locale: &Locale) -> String {
         let number_expr = NumberExpression {
-            parameter: "count".to_string(),
-            format_type: NumberFormatType::Number,
+            parameter: "ratio".to_string(),
+            format_type: NumberFormatType::Percent,
         };
         let message = Message::new(vec![MessageElement::Number(number_expr)]);
+        format_message(&message, params!("ratio" => value), locale).unwrap()
+    }
 
-        let result = format_message(&message, params!("count" => 42));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "42");
+    #[test]
+    fn test_format_percent_keeps_fraction() {
+        assert_eq!(format_percent_in("0.755", &locale!("en-US")), "75.5%");
+        assert_eq!(format_percent_in("12.5", &locale!("en-US")), "1,250%");
     }
 
     #[test]
-    fn test_format_number_decimal() {
+    fn test_format_skeleton_percent_scale() {
+        // A ratio by default, like the bare `percent` style
+        assert_eq!(format_with_skeleton("percent", "0.75").unwrap(), "75%");
+        // The value is already a percentage
+        assert_eq!(format_with_skeleton("percent scale/1", "75").unwrap(), "75%");
+        assert_eq!(format_with_skeleton("percent scale/1 .0", "12.345").unwrap(), "12.3%");
+        assert_eq!(format_with_skeleton("percent .0", "0.12345").unwrap(), "12.3%");
+        assert_eq!(format_with_skeleton("percent scale/1", "-5").unwrap(), "-5%");
+        // Without `percent`, scale just multiplies
+        assert_eq!(format_with_skeleton("scale/0.001", "2500").unwrap(), "2.5");
+        assert_eq!(
+            format_with_skeleton("percent scale/3", "1").unwrap_err(),
+            FormatError::Unsupported("Number scale must be a power of ten: 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_percent_fr() {
+        assert_eq!(format_percent_in("0.755", &locale!("fr-FR")), "75,5\u{a0}%");
+    }
+
+    #[test]
+    fn test_format_percent_negative() {
+        assert_eq!(format_percent_in("-0.25", &locale!("en-US")), "-25%");
+        assert_eq!(format_percent_in("-0.25", &locale!("tr")), "-%25");
+    }
+
+    fn format_currency_in(value: &str, currency: &str, display: CurrencyDisplay, locale: &Locale) -> String {
         let number_expr = NumberExpression {
             parameter: "price".to_string(),
-            format_type: NumberFormatType::Number,
+            format_type: NumberFormatType::Currency(Some(currency.to_string()), display),
         };
         let message = Message::new(vec![MessageElement::Number(number_expr)]);
+        format_message(&message, params!("price" => value), locale).unwrap()
+    }
 
-        let result = format_message(&message, params!("price" => "19.99"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "19.99");
+    #[test]
+    fn test_format_currency_code_display() {
+        assert_eq!(format_currency_in("19.99", "USD", CurrencyDisplay::Code, &locale!("en-US")), "USD\u{a0}19.99");
+        assert_eq!(format_currency_in("19.99", "USD", CurrencyDisplay::Code, &locale!("de-DE")), "19,99\u{a0}USD");
     }
 
     #[test]
-    fn test_format_number_integer() {
-        let number_expr = NumberExpression {
-            parameter: "count".to_string(),
-            format_type: NumberFormatType::Integer,
+    fn test_format_currency_narrow_display() {
+        assert_eq!(format_currency_in("19.99", "USD", CurrencyDisplay::Symbol, &locale!("en-CA")), "US$19.99");
+        assert_eq!(format_currency_in("19.99", "USD", CurrencyDisplay::NarrowSymbol, &locale!("en-CA")), "$19.99");
+    }
+
+    #[test]
+    fn test_format_currency_localized_number() {
+        assert_eq!(format_currency_in("1234.5", "EUR", CurrencyDisplay::Symbol, &locale!("de-DE")), "1.234,50\u{a0}€");
+    }
+
+    #[test]
+    fn test_format_skeleton_currency_iso_code() {
+        assert_eq!(format_with_skeleton("currency/EUR unit-width-iso-code .00", "5").unwrap(), "EUR\u{a0}5.00");
+    }
+
+    #[test]
+    fn test_formatter_cache_reuses_formatters() {
+        let cache = FormatterCache::new(&locale!("en-US"));
+        let first = cache.decimal(GroupingStrategy::Auto).unwrap();
+        let second = cache.decimal(GroupingStrategy::Auto).unwrap();
+        assert!(std::ptr::eq(first, second));
+
+        let ungrouped = cache.decimal(GroupingStrategy::Never).unwrap();
+        assert!(!std::ptr::eq(first, ungrouped));
+    }
+
+    #[test]
+    fn test_formatter_cache_reuses_other_locales() {
+        let cache = FormatterCache::with_fallbacks(&locale!("en-US"), &[locale!("fr")]);
+        assert!(std::ptr::eq(cache.for_locale(&locale!("en-US")), &cache));
+
+        let de = cache.for_locale(&locale!("de"));
+        assert!(std::ptr::eq(de, cache.for_locale(&locale!("de"))));
+        assert!(std::ptr::eq(cache.for_locale(&locale!("ar")), cache.for_locale(&locale!("ar"))));
+        assert!(std::ptr::eq(de, cache.for_locale(&locale!("de"))));
+
+        let numbers = |skeleton: &str| format_skeleton(FixedDecimal::from(1234), skeleton, RoundingMode::HalfEven, &cache).unwrap();
+        assert_eq!(numbers("numbering-system/arab"), numbers("numbering-system/arab"));
+        // de, ar and en-US-u-nu-arab, each cached once
+        let cached = core::iter::successors(cache.other_locales.get(), |other| other.other_locales.get()).count();
+        assert_eq!(cached, 3);
+        assert_eq!(de.fallback.as_deref().map(FormatterCache::locale), Some(&locale!("fr")));
+    }
+
+    #[test]
+    fn test_formatter_cache_mixed_grouping() {
+        let message = Message::new(vec![
+            MessageElement::Number(NumberExpression {
+                parameter: "a".to_string(),
+                format_type: NumberFormatType::Number,
+            }),
+            MessageElement::Text(" ".to_string()),
+            MessageElement::Number(NumberExpression {
+                parameter: "a".to_string(),
+                format_type: NumberFormatType::Skeleton("group-off".to_string()),
+            }),
+        ]);
+        let result = format_message(&message, params!("a" => 12345), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "12,345 12345");
+    }
+
+    #[test]
+    fn test_format_plural_no_matching_case() {
+        let message = crate::parser::parse_message_complete("{count, plural, one{# item}}").unwrap();
+        assert_eq!(format_message(&message, params!("count" => 1), &locale!("en")).unwrap(), "1 item");
+
+        let result = format_message(&message, params!("count" => 5), &locale!("en"));
+        assert_eq!(result, Err(FormatError::NoMatchingCase("count".to_string())));
+    }
+
+    #[test]
+    fn test_format_select_no_matching_case() {
+        let message = crate::parser::parse_message_complete("{g, select, male{he} female{she}}").unwrap();
+        let result = format_message(&message, params!("g" => "other"), &locale!("en"));
+        assert_eq!(result, Err(FormatError::NoMatchingCase("g".to_string())));
+    }
+
+    #[test]
+    fn test_format_skeleton_sign_display() {
+        assert_eq!(format_with_skeleton("sign-always", "5").unwrap(), "+5");
+        assert_eq!(format_with_skeleton("sign-always", "-3").unwrap(), "-3");
+        assert_eq!(format_with_skeleton("sign-always", "0").unwrap(), "+0");
+        assert_eq!(format_with_skeleton("sign-except-zero", "0").unwrap(), "0");
+        assert_eq!(format_with_skeleton("sign-except-zero", "5").unwrap(), "+5");
+        assert_eq!(format_with_skeleton("sign-never", "-3").unwrap(), "3");
+        assert_eq!(format_with_skeleton("sign-auto", "5").unwrap(), "5");
+        assert_eq!(format_with_skeleton("sign-auto", "-3").unwrap(), "-3");
+        assert_eq!(format_with_skeleton("sign-always .00", "1.5").unwrap(), "+1.50");
+    }
+
+    fn format_list_in(items: &[&str], style: ListStyle, locale: &Locale) -> String {
+        let message = Message::new(vec![MessageElement::List(crate::types::ListExpression {
+            parameter: "items".to_string(),
+            style,
+        })]);
+        format_message(&message, params!("items" => items), locale).unwrap()
+    }
+
+    #[test]
+    fn test_format_list_styles() {
+        let names = ["A", "B", "C"];
+        assert_eq!(format_list_in(&names, ListStyle::And, &locale!("en")), "A, B, and C");
+        assert_eq!(format_list_in(&names, ListStyle::Or, &locale!("en")), "A, B, or C");
+        assert_eq!(format_list_in(&names, ListStyle::Unit, &locale!("en")), "A, B, C");
+        assert_eq!(format_list_in(&names, ListStyle::And, &locale!("de")), "A, B und C");
+    }
+
+    #[test]
+    fn test_format_list_empty_and_single() {
+        assert_eq!(format_list_in(&[], ListStyle::And, &locale!("en")), "");
+        assert_eq!(format_list_in(&["A"], ListStyle::And, &locale!("en")), "A");
+        assert_eq!(format_list_in(&["A", "B"], ListStyle::And, &locale!("en")), "A and B");
+    }
+
+    #[test]
+    fn test_format_decimal_parameter_is_exact() {
+        let price: FixedDecimal = "123456789.99".parse().unwrap();
+        let message = crate::parser::parse_message_complete("{p, number, currency/USD} {p}").unwrap();
+        let result = format_message(&message, params!("p" => price), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "$123,456,789.99 123,456,789.99");
+    }
+
+    #[test]
+    fn test_format_decimal_parameter_plural_and_integer() {
+        let count: FixedDecimal = "3".parse().unwrap();
+        let fractional: FixedDecimal = "-2.75".parse().unwrap();
+        let message = crate::parser::parse_message_complete("{c, plural, one{# item} other{# items}} {f, number, integer}").unwrap();
+        let result = format_message(&message, params!("c" => count, "f" => fractional), &locale!("en"));
+        assert_eq!(result.unwrap(), "3 items -2");
+
+        let message = crate::parser::parse_message_complete("{f, plural, other{#}}").unwrap();
+        let result = format_message(&message, params!("f" => fractional), &locale!("en"));
+        assert_eq!(result.unwrap(), "-2.75");
+
+        // Trailing zeros are visible fraction digits, which English counts as `other`
+        let message = crate::parser::parse_message_complete("{c, plural, =1{exactly #} one{# item} other{# items}}").unwrap();
+        let one: FixedDecimal = "1.0".parse().unwrap();
+        assert_eq!(format_message(&message, params!("c" => one), &locale!("en")).unwrap(), "exactly 1.0");
+        let message = crate::parser::parse_message_complete("{c, plural, one{# item} other{# items}}").unwrap();
+        assert_eq!(format_message(&message, params!("c" => one), &locale!("en")).unwrap(), "1.0 items");
+    }
+
+    fn format_with_missing(template: &str, missing_parameter: MissingBehavior) -> Result<String, FormatError> {
+        let message = crate::parser::parse_message_complete(template).unwrap();
+        let options = FormatOptions { missing_parameter, ..Default::default() };
+        format_message_with_options(&message, params!("name" => "Alice"), &locale!("en"), &options)
+    }
+
+    #[test]
+    fn test_format_missing_parameter_behavior() {
+        let template = "Hi {name}, {other} {count, plural, one{# item} other{# items}} {n, number, percent}";
+        assert_eq!(
+            format_with_missing(template, MissingBehavior::Error),
+            Err(FormatError::MissingParameter("other".to_string()))
+        );
+        assert_eq!(
+            format_with_missing(template, MissingBehavior::KeepPlaceholder).unwrap(),
+            "Hi Alice, {other} {count, plural, one{# item} other{# items}} {n, number, percent}"
+        );
+        assert_eq!(format_with_missing(template, MissingBehavior::Empty).unwrap(), "Hi Alice,   ");
+    }
+
+    #[test]
+    fn test_format_missing_parameter_inside_case() {
+        let template = "{name, select, other{{name} and {friend}}}";
+        assert_eq!(format_with_missing(template, MissingBehavior::KeepPlaceholder).unwrap(), "Alice and {friend}");
+    }
+
+    #[test]
+    fn test_format_u64_beyond_i64() {
+        let big = u64::MAX;
+        let message = crate::parser::parse_message_complete("{n} {n, number}").unwrap();
+        let result = format_message(&message, params!("n" => big), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "18446744073709551615 18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn test_format_case_whitespace() {
+        let message = crate::parser::parse_message_complete("[{g, select, male{ He } other{\n  {name}  \n}}]").unwrap();
+        let format = |g: &str, options: &FormatOptions| {
+            format_message_with_options(&message, params!("g" => g, "name" => " Sam "), &locale!("en"), options).unwrap()
         };
-        let message = Message::new(vec![MessageElement::Number(number_expr)]);
 
-        let result = format_message(&message, params!("count" => "19.99"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "19");
+        let keep = FormatOptions::default();
+        assert_eq!(format("male", &keep), "[ He ]");
+        assert_eq!(format("x", &keep), "[\n   Sam   \n]");
+
+        let trim = FormatOptions { trim_case_whitespace: true, ..Default::default() };
+        assert_eq!(format("male", &trim), "[He]");
+        assert_eq!(format("x", &trim), "[ Sam ]");
+    }
+
+    fn format_spellout_in(value: &str, locale: &Locale) -> String {
+        let message = crate::parser::parse_message_complete("{n, number, spellout}").unwrap();
+        format_message(&message, params!("n" => value), locale).unwrap()
     }
 
     #[test]
-    fn test_format_number_percent() {
-        let number_expr = NumberExpression {
-            parameter: "ratio".to_string(),
-            format_type: NumberFormatType::Percent,
+    fn test_format_spellout_english() {
+        let en = locale!("en-US");
+        assert_eq!(format_spellout_in("42", &en), "forty-two");
+        assert_eq!(format_spellout_in("0", &en), "zero");
+        assert_eq!(format_spellout_in("-3", &en), "minus three");
+        assert_eq!(format_spellout_in("100", &en), "one hundred");
+        assert_eq!(format_spellout_in("1234", &en), "one thousand two hundred thirty-four");
+        assert_eq!(format_spellout_in("2000017", &en), "two million seventeen");
+        assert_eq!(format_spellout_in("1.5", &en), "one point five");
+    }
+
+    #[test]
+    fn test_format_spellout_falls_back_to_digits() {
+        assert_eq!(format_spellout_in("1234", &locale!("fr")), "1\u{202f}234");
+    }
+
+    fn format_ordinal_in(value: &str, locale: &Locale) -> String {
+        let message = crate::parser::parse_message_complete("{n, number, ordinal}").unwrap();
+        format_message(&message, params!("n" => value), locale).unwrap()
+    }
+
+    #[test]
+    fn test_format_ordinal() {
+        let en = locale!("en");
+        let results: Vec<String> = ["1", "2", "3", "4", "11", "12", "13", "21", "102", "1003"]
+            .iter()
+            .map(|n| format_ordinal_in(n, &en))
+            .collect();
+        assert_eq!(results, ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "1,003rd"]);
+
+        assert_eq!(format_ordinal_in("1", &locale!("fr")), "1er");
+        assert_eq!(format_ordinal_in("2", &locale!("fr")), "2e");
+        assert_eq!(format_ordinal_in("3", &locale!("de")), "3.");
+        assert_eq!(format_ordinal_in("3", &locale!("ja")), "3");
+        assert_eq!(format_ordinal_in("3.00", &en), "3rd");
+
+        let message = crate::parser::parse_message_complete("{n, number, ordinal}").unwrap();
+        assert_eq!(
+            format_message(&message, params!("n" => 3.7), &en),
+            Err(FormatError::InvalidParameterType { parameter: "n".to_string(), expected: "integer", found: "number" })
+        );
+    }
+
+    #[test]
+    fn test_format_case_insensitive_select() {
+        let message = crate::parser::parse_message_complete("{g, select, male{He} Female{She} straße{Street} other{They}}").unwrap();
+        let format = |g: &str, case_insensitive_select: bool| {
+            let options = FormatOptions { case_insensitive_select, ..Default::default() };
+            format_message_with_options(&message, params!("g" => g), &locale!("en"), &options).unwrap()
         };
-        let message = Message::new(vec![MessageElement::Number(number_expr)]);
 
-        let result = format_message(&message, params!("ratio" => "0.75"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "75%");
+        assert_eq!(format("MALE", false), "They");
+        assert_eq!(format("MALE", true), "He");
+        assert_eq!(format("female", true), "She");
+        // Full case folding, not just lowercasing
+        assert_eq!(format("STRASSE", true), "Street");
     }
 
     #[test]
-    fn test_format_number_currency_usd() {
-        let number_expr = NumberExpression {
-            parameter: "price".to_string(),
-            format_type: NumberFormatType::Currency("USD".to_string()),
+    fn test_format_max_output_bytes() {
+        let message = crate::parser::parse_message_complete("{n, plural, other{# # # # {n, plural, other{# # # #}}}}").unwrap();
+        let format = |max_output_bytes| {
+            let options = FormatOptions { max_output_bytes, ..Default::default() };
+            format_message_with_options(&message, params!("n" => 1_000_000_000), &locale!("en"), &options)
         };
-        let message = Message::new(vec![MessageElement::Number(number_expr)]);
 
-        let result = format_message(&message, params!("price" => "19.99"));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "$19.99");
+        assert_eq!(format(None).unwrap().len(), 8 * 13 + 7);
+        assert_eq!(format(Some(111)).unwrap().len(), 111);
+        let error = format(Some(64)).unwrap_err();
+        assert_eq!(error, FormatError::OutputTooLarge { limit: 64 });
+        assert_eq!(error.to_string(), "Output exceeds 64 bytes");
+
+        let options = FormatOptions { max_output_bytes: Some(20), ..Default::default() };
+        let result = format_to_parts_with_options(&message, params!("n" => 5), &locale!("en"), &options);
+        assert_eq!(result.unwrap().len(), 15);
+        let result = format_to_parts_with_options(&message, params!("n" => 123456), &locale!("en"), &options);
+        assert_eq!(result, Err(FormatError::OutputTooLarge { limit: 20 }));
+
+        // Trimmed whitespace doesn't count towards the limit
+        let message = crate::parser::parse_message_complete("{n, plural, other{   # items   }}").unwrap();
+        let options = FormatOptions { max_output_bytes: Some(7), trim_case_whitespace: true, ..Default::default() };
+        let result = format_to_parts_with_options(&message, params!("n" => 5), &locale!("en"), &options);
+        assert_eq!(result.unwrap().len(), 2);
     }
 
     #[test]
-    fn test_format_number_currency_eur() {
-        let number_expr = NumberExpression {
-            parameter: "price".to_string(),
-            format_type: NumberFormatType::Currency("EUR".to_string()),
+    fn test_format_strict_select() {
+        let message = crate::parser::parse_message_complete("{plan, select, free{Free} pro{Pro} other{Custom}}").unwrap();
+        let format = |plan: &str, strict_select: bool, case_insensitive_select: bool| {
+            let options = FormatOptions { strict_select, case_insensitive_select, ..Default::default() };
+            format_message_with_options(&message, params!("plan" => plan), &locale!("en"), &options)
         };
-        let message = Message::new(vec![MessageElement::Number(number_expr)]);
 
-        let result = format_message(&message, params!("price" => 25));
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "€25");
+        assert_eq!(format("enterprise", false, false).unwrap(), "Custom");
+        assert_eq!(format("pro", true, false).unwrap(), "Pro");
+        assert_eq!(format("PRO", true, true).unwrap(), "Pro");
+
+        let error = format("enterprise", true, false).unwrap_err();
+        assert_eq!(error, FormatError::UnmatchedSelect { parameter: "plan".to_string(), value: "enterprise".to_string() });
+        assert_eq!(error.to_string(), "Unlisted select value for plan: enterprise");
+        // Naming the fallback doesn't count as listing the value
+        assert!(matches!(format("other", true, false), Err(FormatError::UnmatchedSelect { .. })));
+        assert!(matches!(format("PRO", true, false), Err(FormatError::UnmatchedSelect { .. })));
     }
 
     #[test]
-    fn test_format_number_currency_any_valid_code() {
-        let number_expr = NumberExpression {
-            parameter: "price".to_string(),
-            format_type: NumberFormatType::Currency("SEK".to_string()),
+    fn test_format_case_of_plural() {
+        let message = crate::parser::parse_message_complete("{n, plural, =0{No files} one{# file by {who}} other{# files}}").unwrap();
+        let plural = &message.elements[0];
+        let format = |selector: &str| format_case(plural, selector, params!("n" => 1200, "who" => "Ada"), &locale!("en"));
+
+        // Each case is formatted whichever one the count would pick
+        assert_eq!(format("one").unwrap(), "1,200 file by Ada");
+        assert_eq!(format("other").unwrap(), "1,200 files");
+        assert_eq!(format("=0").unwrap(), "No files");
+
+        let error = format("few").unwrap_err();
+        assert_eq!(error, FormatError::UnknownCase { parameter: "n".to_string(), selector: "few".to_string() });
+        assert_eq!(error.to_string(), "No case `few` for n");
+        assert_eq!(
+            format_case(plural, "other", params!(), &locale!("en")).unwrap_err(),
+            FormatError::MissingParameter("n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_case_of_select() {
+        let message = crate::parser::parse_message_complete("{g, select, male{He has {n, plural, one{# item} other{# items}}} other{They}} {name}").unwrap();
+        let select = &message.elements[0];
+        assert_eq!(format_case(select, "male", params!("g" => "female", "n" => 1), &locale!("en")).unwrap(), "He has 1 item");
+        assert_eq!(format_case(select, "other", params!(), &locale!("en")).unwrap(), "They");
+        assert!(matches!(format_case(select, "female", params!(), &locale!("en")), Err(FormatError::UnknownCase { .. })));
+        assert!(matches!(format_case(&message.elements[2], "other", params!(), &locale!("en")), Err(FormatError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_format_bidi_isolation() {
+        let message = crate::parser::parse_message_complete("שלום {name}, {n, number} {g, select, other{טקסט}} {k, plural, other{# קבצים}}").unwrap();
+        let format = |locale: &Locale, bidi_isolation: bool| {
+            let options = FormatOptions { bidi_isolation, ..Default::default() };
+            format_message_with_options(&message, params!("name" => "Sam", "n" => 5, "g" => "x", "k" => 3), locale, &options).unwrap()
         };
-        let message = Message::new(vec![MessageElement::Number(number_expr)]);
 
-        let result = format_message(&message, params!("price" => 100));
-        assert!(result.is_ok());
-        // ICU4X should handle SEK (Swedish Krona) even though we didn't hardcode it
-        let formatted = result.unwrap();
-        assert!(formatted.contains("100") || formatted.contains("SEK"));
+        assert_eq!(format(&locale!("he"), true), "שלום \u{2068}Sam\u{2069}, \u{2068}5\u{2069} טקסט \u{2068}3\u{2069} קבצים");
+        assert_eq!(format(&locale!("he"), false), "שלום Sam, 5 טקסט 3 קבצים");
+        assert_eq!(format(&locale!("en"), true), "שלום Sam, 5 טקסט 3 קבצים");
+    }
+
+    #[test]
+    fn test_format_collecting_errors() {
+        let message = crate::parser::parse_message_complete(
+            "{a} {b, number} {n, plural, one{# {c}} other{# {d} {e, date}}} {ok}",
+        )
+        .unwrap();
+        let result = format_collecting_errors(&message, params!("b" => "abc", "n" => 2, "e" => "soon", "ok" => "fine"), &locale!("en"));
+        assert_eq!(result, Err(vec![
+            FormatError::MissingParameter("a".to_string()),
+            FormatError::InvalidParameterType { parameter: "b".to_string(), expected: "number", found: "string" },
+            FormatError::MissingParameter("d".to_string()),
+            FormatError::InvalidParameterType { parameter: "e".to_string(), expected: "ISO date string", found: "string" },
+        ]));
+
+        let message = crate::parser::parse_message_complete("{ok}").unwrap();
+        assert_eq!(format_collecting_errors(&message, params!("ok" => "fine"), &locale!("en")), Ok("fine".to_string()));
+    }
+
+    #[test]
+    fn test_format_negative_currency() {
+        assert_eq!(format_currency_in("-5", "USD", CurrencyDisplay::Symbol, &locale!("en-US")), "-$5.00");
+        assert_eq!(format_currency_in("-19.99", "EUR", CurrencyDisplay::Symbol, &locale!("de-DE")), "-19,99\u{a0}€");
+        assert_eq!(format_currency_in("-19.99", "USD", CurrencyDisplay::Code, &locale!("en-US")), "-USD\u{a0}19.99");
+        // CLDR writes `¤ -#,##0.00` for nl, but the baked data only has the positive
+        // pattern, so the sign still goes in front
+        assert_eq!(format_currency_in("-19.99", "EUR", CurrencyDisplay::Symbol, &locale!("nl")), "-€\u{a0}19,99");
+    }
+
+    #[test]
+    fn test_format_skeleton_currency_accounting() {
+        assert_eq!(format_with_skeleton("currency/USD", "-5").unwrap(), "-$5.00");
+        assert_eq!(format_with_skeleton("currency/USD sign-accounting", "-5").unwrap(), "($5.00)");
+        assert_eq!(format_with_skeleton("currency/USD sign-accounting", "5").unwrap(), "$5.00");
+        assert_eq!(format_with_skeleton("currency/USD sign-always", "5").unwrap(), "+$5.00");
+    }
+
+    #[test]
+    fn test_format_currency_from_parameter() {
+        let message = crate::parser::parse_message_complete("{price, number, currency/$code}").unwrap();
+        let format = |code: &str| format_message(&message, params!("price" => 5, "code" => code), &locale!("en-US"));
+
+        assert_eq!(format("EUR").unwrap(), "€5.00");
+        assert_eq!(format("jpy").unwrap(), "¥5");
+        assert_eq!(
+            format("EURO"),
+            Err(FormatError::InvalidParameterType { parameter: "code".to_string(), expected: "3-letter currency code", found: "string" })
+        );
+
+        let result = format_message(&message, params!("price" => 5), &locale!("en-US"));
+        assert_eq!(result, Err(FormatError::MissingParameter("code".to_string())));
+
+        let format_missing = |missing_parameter| {
+            let options = FormatOptions { missing_parameter, ..Default::default() };
+            format_message_with_options(&message, params!("price" => 5), &locale!("en-US"), &options)
+        };
+        assert_eq!(format_missing(MissingBehavior::KeepPlaceholder).unwrap(), "{price, number, currency/$code}");
+        assert_eq!(format_missing(MissingBehavior::Empty).unwrap(), "");
+    }
+
+    #[test]
+    fn test_invalid_parameter_type_message() {
+        let message = crate::parser::parse_message_complete("{gender, select, female{she} other{they}}").unwrap();
+        let error = format_message(&message, params!("gender" => 3), &locale!("en")).unwrap_err();
+        assert_eq!(
+            error,
+            FormatError::InvalidParameterType { parameter: "gender".to_string(), expected: "string", found: "number" }
+        );
+        assert_eq!(error.to_string(), "parameter `gender`: expected string, found number");
+
+        let message = crate::parser::parse_message_complete("{names, list}").unwrap();
+        let error = format_message(&message, params!("names" => true), &locale!("en")).unwrap_err();
+        assert_eq!(error.to_string(), "parameter `names`: expected list, found boolean");
+    }
+
+    #[test]
+    fn test_format_default_currency_from_locale() {
+        let message = crate::parser::parse_message_complete("{price, number, currency}").unwrap();
+        let format = |locale: &Locale| format_message(&message, params!("price" => 5), locale).unwrap();
+
+        assert_eq!(format(&locale!("en-US")), "$5.00");
+        assert_eq!(format(&locale!("fr-FR")), "5,00\u{a0}€");
+        assert_eq!(format(&locale!("ja-JP")), "￥5");
+        // Without a region the likely one is used
+        assert_eq!(format(&locale!("fr")), "5,00\u{a0}€");
+        assert_eq!(format(&locale!("en-GB")), "£5.00");
+
+        let message = crate::parser::parse_message_complete("{price, number, currency/JPY}").unwrap();
+        assert_eq!(format_message(&message, params!("price" => 5), &locale!("fr-FR")).unwrap(), "5\u{a0}JPY");
+    }
+
+    #[test]
+    fn test_format_with_fallback_locales() {
+        let message = crate::parser::parse_message_complete("{n, plural, one{# item} other{# items}} for {price, number, currency}").unwrap();
+        // Antarctica has no currency of its own
+        let result = format_message(&message, params!("n" => 2, "price" => 5), &locale!("en-AQ"));
+        assert!(matches!(result, Err(FormatError::Unsupported(_))));
+
+        let result = format_message_with_fallbacks(&message, params!("n" => 2, "price" => 5), &locale!("en-AQ"), &[locale!("und-AQ"), locale!("en-GB")]);
+        assert_eq!(result.unwrap(), "2 items for £5.00");
+
+        let result = format_message_with_fallbacks(&message, params!("n" => 2, "price" => 5), &locale!("en-AQ"), &[]);
+        assert!(matches!(result, Err(FormatError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_format_currency_fraction_digits() {
+        let message = crate::parser::parse_message_complete("{price, number, currency/JPY}").unwrap();
+        let format = |price: f64| format_message(&message, params!("price" => price), &locale!("en-US")).unwrap();
+        assert_eq!(format(1234.0), "¥1,234");
+        assert_eq!(format(100.4), "¥100");
+        assert_eq!(format(100.6), "¥101");
+        // Half-even by default, like skeleton precision
+        assert_eq!(format(100.5), "¥100");
+
+        let message = crate::parser::parse_message_complete("{a, number, currency/USD} {b, number, currency/KWD/code}").unwrap();
+        let result = format_message(&message, params!("a" => 19.999, "b" => 1.23456), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "$20.00 KWD\u{a0}1.235");
+
+        let message = crate::parser::parse_message_complete("{a, number, currency/CLF/code} {b, number, currency/SLE/code}").unwrap();
+        let result = format_message(&message, params!("a" => 1.234567, "b" => 1.234567), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "CLF\u{a0}1.2346 SLE\u{a0}1.23");
+
+        assert_eq!(format_with_skeleton("currency/JPY .00", "100.5").unwrap(), "¥100.50");
+        assert_eq!(format_with_skeleton("currency/JPY", "100.6").unwrap(), "¥101");
+    }
+
+    #[test]
+    fn test_format_plural_hash_grouped() {
+        let message = crate::parser::parse_message_complete("{n, plural, one{# item} other{# items}}").unwrap();
+        let result = format_message(&message, params!("n" => 1234567), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "1,234,567 items");
+
+        let result = format_message(&message, params!("n" => 1234567), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1.234.567 items");
+
+        let result = format_message(&message, params!("n" => -1000), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "-1,000 items");
+    }
+
+    #[test]
+    fn test_format_message_to_sink() {
+        let message = crate::parser::parse_message_complete("{n, plural, one{# file} other{# files}} in {dir}").unwrap();
+        let mut out = String::from("> ");
+        format_message_to(&message, params!("n" => 1200, "dir" => "/tmp"), &locale!("en"), &mut out).unwrap();
+        assert_eq!(out, "> 1,200 files in /tmp");
+
+        let mut out = String::new();
+        let result = format_message_to(&message, params!("n" => 1), &locale!("en"), &mut out);
+        assert_eq!(result, Err(FormatError::MissingParameter("dir".to_string())));
+        assert_eq!(out, "1 file in ");
+
+        struct Full;
+
+        impl core::fmt::Write for Full {
+            fn write_str(&mut self, _: &str) -> core::fmt::Result {
+                Err(core::fmt::Error)
+            }
+        }
+
+        let result = format_message_to(&message, params!("n" => 1, "dir" => "/tmp"), &locale!("en"), &mut Full);
+        assert_eq!(result, Err(FormatError::Write));
+
+        let options = FormatOptions { max_output_bytes: Some(12), trim_case_whitespace: true, ..Default::default() };
+        let message = crate::parser::parse_message_complete("{n, plural, other{ # files }} in {dir}").unwrap();
+        let mut out = String::new();
+        let result = format_message_to_with_options(&message, params!("n" => 1200, "dir" => "/tmp"), &locale!("en"), &options, &mut out);
+        assert_eq!(result, Err(FormatError::OutputTooLarge { limit: 12 }));
+        assert_eq!(out, "1,200 files");
+    }
+
+    #[test]
+    fn test_plural_categories_for() {
+        use PluralSelector::*;
+
+        assert_eq!(plural_categories_for(&locale!("en")).unwrap(), [One, Other]);
+        assert_eq!(plural_categories_for(&locale!("ar")).unwrap(), [Zero, One, Two, Few, Many, Other]);
+        assert_eq!(plural_categories_for(&locale!("ja")).unwrap(), [Other]);
+        assert_eq!(plural_categories_for(&locale!("pl-PL")).unwrap(), [One, Few, Many, Other]);
+    }
+
+    #[test]
+    fn test_format_select_custom_fallback() {
+        let message = crate::parser::parse_message_complete("{g, select, female{she} default{they} other{it}}").unwrap();
+        let options = FormatOptions { select_fallback: "default".to_string(), ..Default::default() };
+        let format = |g: &str, options: &FormatOptions| format_message_with_options(&message, params!("g" => g), &locale!("en"), options);
+
+        assert_eq!(format("female", &options).unwrap(), "she");
+        assert_eq!(format("male", &options).unwrap(), "they");
+        // `other` is an ordinary selector once the fallback is renamed
+        assert_eq!(format("other", &options).unwrap(), "it");
+        assert_eq!(format("male", &FormatOptions::default()).unwrap(), "it");
+
+        let message = crate::parser::parse_message_complete("{g, select, female{she} other{it}}").unwrap();
+        let result = format_message_with_options(&message, params!("g" => "male"), &locale!("en"), &options);
+        assert_eq!(result, Err(FormatError::NoMatchingCase("g".to_string())));
+    }
+
+    #[test]
+    fn test_format_interpret_escapes() {
+        let escapes = FormatOptions { interpret_escapes: true, ..Default::default() };
+        let format = |template: &str, options: &FormatOptions| {
+            let message = crate::parser::parse_message_complete(template).unwrap();
+            format_message_with_options(&message, params!("name" => r"a\nb", "n" => 2), &locale!("en"), options).unwrap()
+        };
+
+        assert_eq!(format("Dear {name},\n\nThanks", &FormatOptions::default()), "Dear a\\nb,\n\nThanks");
+        assert_eq!(format("Dear {name},\n\nThanks", &escapes), "Dear a\\nb,\n\nThanks");
+
+        assert_eq!(format(r"Line 1\nLine 2", &FormatOptions::default()), r"Line 1\nLine 2");
+        assert_eq!(format(r"Line 1\nLine 2", &escapes), "Line 1\nLine 2");
+        assert_eq!(format(r"C:\\new \t {n, plural, other{#\n}}", &escapes), "C:\\new \\t 2\n");
+    }
+
+    #[test]
+    fn test_format_scientific_and_engineering() {
+        let format = |template: &str, v: &str, locale: &Locale| {
+            let message = crate::parser::parse_message_complete(template).unwrap();
+            let v: FixedDecimal = v.parse().unwrap();
+            format_message(&message, params!("v" => v), locale).unwrap()
+        };
+        let scientific = |v: &str| format("{v, number, scientific}", v, &locale!("en"));
+        let engineering = |v: &str| format("{v, number, engineering}", v, &locale!("en"));
+
+        assert_eq!(scientific("42000"), "4.2E4");
+        assert_eq!(scientific("-42000"), "-4.2E4");
+        assert_eq!(scientific("0.00123"), "1.23E-3");
+        assert_eq!(scientific("7"), "7E0");
+        assert_eq!(scientific("0"), "0E0");
+        assert_eq!(engineering("42000"), "42E3");
+        assert_eq!(engineering("1234567"), "1.234567E6");
+        assert_eq!(engineering("0.00123"), "1.23E-3");
+        assert_eq!(engineering("0.000123"), "123E-6");
+        assert_eq!(engineering("-0.5"), "-500E-3");
+
+        assert_eq!(format("{v, number, scientific}", "-42000.5", &locale!("de")), "-4,20005E4");
+        assert_eq!(format_with_skeleton("scientific .00", "9996").unwrap(), "1.00E4");
+        assert_eq!(format_with_skeleton("engineering .0 sign-always", "12345").unwrap(), "+12.3E3");
+    }
+
+    #[test]
+    fn test_format_skeleton_integer_width() {
+        assert_eq!(format_with_skeleton("integer-width/000", "7").unwrap(), "007");
+        assert_eq!(format_with_skeleton("integer-width/*000", "7").unwrap(), "007");
+        assert_eq!(format_with_skeleton("integer-width/*00", "12345").unwrap(), "12,345");
+        assert_eq!(format_with_skeleton("integer-width/*00 group-off .00", "-5.5").unwrap(), "-05.50");
+        assert_eq!(format_with_skeleton("integer-width/*00", "0.5").unwrap(), "00.5");
+        // Without `*` the width is also a maximum, so higher digits are dropped
+        assert_eq!(format_with_skeleton("integer-width/000", "1234").unwrap(), "234");
+        assert_eq!(format_with_skeleton("integer-width/##0", "12345").unwrap(), "345");
+        assert_eq!(format_with_skeleton("integer-width/##0", "5").unwrap(), "5");
+
+        assert!(format_with_skeleton("integer-width/0#", "5").is_err());
+        assert!(format_with_skeleton("integer-width/*#0", "5").is_err());
+    }
+
+    #[test]
+    fn test_format_skeleton_padding() {
+        assert_eq!(format_with_skeleton("pad/4", "5").unwrap(), "   5");
+        assert_eq!(format_with_skeleton("pad-end/4", "5").unwrap(), "5   ");
+        assert_eq!(format_with_skeleton("pad/6/*", "-5").unwrap(), "****-5");
+        // Separators and symbols count towards the width
+        assert_eq!(format_with_skeleton("pad/7", "1234").unwrap(), "  1,234");
+        assert_eq!(format_with_skeleton("pad/7 group-off", "1234").unwrap(), "   1234");
+        assert_eq!(format_with_skeleton("pad/8 currency/USD .00", "5").unwrap(), "   $5.00");
+        assert_eq!(format_with_skeleton("pad/2", "12345").unwrap(), "12,345");
+
+        assert!(format_with_skeleton("pad/x", "5").is_err());
+        assert!(format_with_skeleton("pad/4/ab", "5").is_err());
+    }
+
+    #[test]
+    fn test_format_skeleton_approximately() {
+        assert_eq!(format_with_skeleton("approximately", "5").unwrap(), "~5");
+        assert_eq!(format_with_skeleton("approximately compact-short", "1234").unwrap(), "~1.2K");
+        assert_eq!(format_with_skeleton("currency/USD approximately .00", "5").unwrap(), "~$5.00");
+        assert_eq!(format_with_skeleton("approximately pad/4", "5").unwrap(), "  ~5");
+
+        let message = crate::parser::parse_message_complete("About {n, number, ::approximately compact-short} views").unwrap();
+        assert_eq!(format_message(&message, params!("n" => 3_400_000), &locale!("en")).unwrap(), "About ~3.4M views");
     }
 }
@@ -0,0 +1,198 @@
+use crate::types::{Message, MessageElement};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use core::fmt::Write;
+
+// Shares identical plural and select cases between the messages it interns, so a
+// catalog repeating the same `other{...}` holds it once. Each interned case becomes a
+// single `MessageElement::Shared`; messages that are never interned stay fully owned.
+#[derive(Debug, Default)]
+pub struct MessageInterner {
+    // Keyed by `case_key`, which is only unique while the interner holds every
+    // shared message a key points at
+    cases: BTreeMap<String, Arc<Message>>,
+}
+
+impl MessageInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Nested cases are interned before the case containing them, so two cases that
+    // only share an inner plural still share that plural
+    pub fn intern(&mut self, message: &mut Message) {
+        for element in &mut message.elements {
+            match element {
+                MessageElement::Plural(plural_expr) => {
+                    for case in &mut plural_expr.cases {
+                        self.intern_case(&mut case.message);
+                    }
+                }
+                MessageElement::Select(select_expr) => {
+                    for case in &mut select_expr.cases {
+                        self.intern_case(&mut case.message);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // The number of distinct cases held
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+
+    fn intern_case(&mut self, case: &mut Message) {
+        if let [MessageElement::Shared(_)] = case.elements.as_slice() {
+            return;
+        }
+        self.intern(case);
+
+        let mut key = String::new();
+        case_key(case, &mut key);
+        let shared = self.cases.entry(key).or_insert_with(|| Arc::new(core::mem::replace(case, Message::new(vec![]))));
+        *case = Message::new(vec![MessageElement::Shared(Arc::clone(shared))]);
+    }
+}
+
+// Identifies a case whose own cases are already interned. Those are written as the
+// address of their shared message rather than in full, so each level of nesting is
+// written once. Everything else uses `Debug`, which quotes text, so texts split
+// differently don't collide the way they would as ICU source.
+fn case_key(message: &Message, key: &mut String) {
+    key.push('[');
+    for element in &message.elements {
+        // Writing to a `String` can't fail
+        let _ = match element {
+            MessageElement::Shared(shared) => write!(key, "Shared({:p})", Arc::as_ptr(shared)),
+            MessageElement::Plural(plural_expr) => {
+                let _ = write!(key, "Plural({:?}, {:?}", plural_expr.parameter, plural_expr.hash_skeleton);
+                for case in &plural_expr.cases {
+                    let _ = write!(key, ", {:?}: ", case.selector);
+                    case_key(&case.message, key);
+                }
+                write!(key, ")")
+            }
+            MessageElement::Select(select_expr) => {
+                let _ = write!(key, "Select({:?}", select_expr.parameter);
+                for case in &select_expr.cases {
+                    let _ = write!(key, ", {:?}: ", case.selector);
+                    case_key(&case.message, key);
+                }
+                write!(key, ")")
+            }
+            element => write!(key, "{element:?}"),
+        };
+        key.push(',');
+    }
+    key.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_message_complete;
+    use crate::types::PluralExpression;
+    use alloc::string::ToString;
+
+    fn plural(message: &Message) -> &PluralExpression {
+        match &message.elements[0] {
+            MessageElement::Plural(plural_expr) => plural_expr,
+            element => panic!("expected a plural, found {element:?}"),
+        }
+    }
+
+    fn shared(case: &Message) -> &Arc<Message> {
+        match case.elements.as_slice() {
+            [MessageElement::Shared(message)] => message,
+            _ => panic!("case was not shared: {case}"),
+        }
+    }
+
+    #[test]
+    fn test_intern_shares_identical_cases() {
+        let mut interner = MessageInterner::new();
+        let mut first = parse_message_complete("{n, plural, one{# file} other{# files}}").unwrap();
+        let mut second = parse_message_complete("{count, plural, =0{none} other{# files}}").unwrap();
+        let original = second.clone();
+        interner.intern(&mut first);
+        interner.intern(&mut second);
+
+        let other = shared(&plural(&first).cases[1].message);
+        assert!(Arc::ptr_eq(other, shared(&plural(&second).cases[1].message)));
+        // The interner and both messages hold the one allocation
+        assert_eq!(Arc::strong_count(other), 3);
+        assert_eq!(interner.len(), 3);
+
+        // Sharing changes neither equality nor source
+        assert_eq!(second, original);
+        assert_eq!(second.to_string(), "{count, plural, =0{none} other{# files}}");
+    }
+
+    #[test]
+    fn test_interned_message_formats_like_original() {
+        use crate::formatter::{FormatOptions, format_message_with_options, format_to_parts_with_options};
+
+        let original = parse_message_complete("{g, select, male{ He has {n, plural, one{# file} other{# files}} } other{ They }}!").unwrap();
+        let mut interned = original.clone();
+        MessageInterner::new().intern(&mut interned);
+
+        let options = FormatOptions { trim_case_whitespace: true, ..Default::default() };
+        let locale = icu::locid::locale!("en");
+        for g in ["male", "female"] {
+            let format = |message: &Message| format_message_with_options(message, crate::params!("g" => g, "n" => 1), &locale, &options);
+            assert_eq!(format(&interned), format(&original));
+            let parts = |message: &Message| format_to_parts_with_options(message, crate::params!("g" => g, "n" => 1), &locale, &options);
+            assert_eq!(parts(&interned), parts(&original));
+        }
+        assert_eq!(format_message_with_options(&interned, crate::params!("g" => "male", "n" => 1), &locale, &options).unwrap(), "He has 1 file!");
+    }
+
+    #[test]
+    fn test_intern_shares_nested_cases() {
+        let mut interner = MessageInterner::new();
+        let mut message = parse_message_complete(
+            "{g, select, male{He: {n, plural, other{# items}}} other{They: {n, plural, other{# items}}}}",
+        )
+        .unwrap();
+        interner.intern(&mut message);
+
+        let MessageElement::Select(select_expr) = &message.elements[0] else {
+            panic!("expected a select");
+        };
+        let inner = |index: usize| match &shared(&select_expr.cases[index].message).elements[1] {
+            MessageElement::Plural(plural_expr) => shared(&plural_expr.cases[0].message),
+            element => panic!("expected a plural, found {element:?}"),
+        };
+        assert!(Arc::ptr_eq(inner(0), inner(1)));
+    }
+
+    #[test]
+    fn test_intern_keeps_unequal_cases_apart() {
+        // Both print as `ab`, but only equal messages may be shared
+        let split = Message::new(vec![MessageElement::Text("a".to_string()), MessageElement::Text("b".to_string())]);
+        let joined = Message::new(vec![MessageElement::Text("ab".to_string())]);
+        let with_case = |case: Message| {
+            let mut message = parse_message_complete("{g, select, other{}}").unwrap();
+            if let MessageElement::Select(select_expr) = &mut message.elements[0] {
+                select_expr.cases[0].message = case;
+            }
+            message
+        };
+        let (mut first, mut second) = (with_case(split), with_case(joined));
+        let expected = second.clone();
+
+        let mut interner = MessageInterner::new();
+        interner.intern(&mut first);
+        interner.intern(&mut second);
+        assert_eq!(second, expected);
+        assert_eq!(interner.len(), 2);
+    }
+}
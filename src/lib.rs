@@ -1,32 +1,61 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod compiled;
 pub mod formatter;
+pub mod intern;
 pub mod parser;
 pub mod types;
+pub mod validate;
+pub mod visit;
+
+pub use compiled::CompiledMessage;
+pub use formatter::{Escaper, FormatError, FormatOptions, FormatPart, MissingBehavior, RoundingMode, format_case, format_collecting_errors, format_message, format_message_to, format_message_to_with_options, format_message_with_fallbacks, format_message_with_options, format_to_parts, format_to_parts_with_options, plural_categories_for};
+pub use intern::MessageInterner;
+pub use parser::{DEFAULT_MAX_DEPTH, ParseError, ParseErrorKind, parse_message, parse_message_borrowed, parse_message_complete, parse_message_with_max_depth};
+pub use types::{Message, MessageElement, ElementKind, BorrowedMessage, BorrowedElement, BorrowedPluralCase, BorrowedSelectCase, PluralSelector, ParameterSource, Merged, ParameterValue, Parameters, DuplicateKeyError, ParametersBuilder, OwnedParameters, OwnedParameterValue, ListItems, SelectExpression, SelectCase, NumberExpression, NumberFormatType, CurrencyDisplay, CompactStyle, DateExpression, DateTimeStyle, TimeExpression, ListExpression, ListStyle};
+pub use validate::{ValidationError, ValidationErrorKind};
+pub use visit::{MessageFolder, MessageVisitor};
+pub use fixed_decimal::FixedDecimal;
+pub use icu::locid::{Locale, ParserError as LocaleParseError};
 
-pub use formatter::{FormatError, format_message};
-pub use parser::parse_message;
-pub use types::{Message, MessageElement, ParameterValue, Parameters, SelectExpression, SelectCase, NumberExpression, NumberFormatType};
-pub use icu::locid::Locale;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 #[derive(Debug)]
 pub enum MessageFormatError {
-    ParseError(String),
+    ParseError(ParseError),
     FormatError(FormatError),
+    LocaleError(LocaleParseError),
 }
 
-impl std::fmt::Display for MessageFormatError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MessageFormatError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             MessageFormatError::ParseError(msg) => write!(f, "Parse error: {msg}"),
             MessageFormatError::FormatError(err) => write!(f, "Format error: {err}"),
+            MessageFormatError::LocaleError(err) => write!(f, "Invalid locale: {err}"),
         }
     }
 }
 
-impl std::error::Error for MessageFormatError {}
+// A `ParseError` has no source of its own: it is diagnosed from the template itself,
+// and nom's error, which only records which combinator failed, is not kept
+impl core::error::Error for MessageFormatError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            MessageFormatError::ParseError(err) => Some(err),
+            MessageFormatError::FormatError(err) => Some(err),
+            // ICU's locale errors only implement `Error` behind its own `std` feature
+            MessageFormatError::LocaleError(_) => None,
+        }
+    }
+}
 
-impl From<nom::Err<nom::error::Error<&str>>> for MessageFormatError {
-    fn from(err: nom::Err<nom::error::Error<&str>>) -> Self {
-        MessageFormatError::ParseError(format!("{err:?}"))
+impl From<ParseError> for MessageFormatError {
+    fn from(err: ParseError) -> Self {
+        MessageFormatError::ParseError(err)
     }
 }
 
@@ -36,18 +65,101 @@ impl From<FormatError> for MessageFormatError {
     }
 }
 
-pub fn format<'a>(
+impl From<LocaleParseError> for MessageFormatError {
+    fn from(err: LocaleParseError) -> Self {
+        MessageFormatError::LocaleError(err)
+    }
+}
+
+// Parses a BCP-47 language tag such as `de-DE`, e.g. from an `Accept-Language` entry
+pub fn parse_locale(tag: &str) -> Result<Locale, MessageFormatError> {
+    Ok(tag.trim().parse::<Locale>()?)
+}
+
+// Parses a template without formatting it, rejecting any trailing unparsed input
+pub fn parse(input: &str) -> Result<Message, MessageFormatError> {
+    Ok(parse_message_complete(input)?)
+}
+
+impl core::str::FromStr for Message {
+    type Err = MessageFormatError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse(input)
+    }
+}
+
+impl TryFrom<&str> for Message {
+    type Error = MessageFormatError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        parse(input)
+    }
+}
+
+// Formats a message parsed ahead of time, e.g. with `Message::try_from`, so that
+// template errors surface when it's built and only format errors surface here
+pub fn format_parsed<P: ParameterSource>(
+    message: &Message,
+    parameters: P,
+    locale: &Locale,
+) -> Result<String, MessageFormatError> {
+    Ok(format_message(message, parameters, locale)?)
+}
+
+pub fn format<P: ParameterSource>(
+    message_str: &str,
+    parameters: P,
+) -> Result<String, MessageFormatError> {
+    format_with_locale(message_str, parameters, &Locale::default())
+}
+
+pub fn format_with_locale<P: ParameterSource>(
     message_str: &str,
-    parameters: Parameters<'a>,
+    parameters: P,
+    locale: &Locale,
 ) -> Result<String, MessageFormatError> {
-    let (_, message) = parse_message(message_str)?;
-    let result = format_message(&message, parameters)?;
+    let message = parse(message_str)?;
+    let result = format_message(&message, parameters, locale)?;
     Ok(result)
 }
 
+// Formats like `format_with_locale`, but in debug builds panics when the `params!`
+// keys don't match the template's parameters, to catch typos such as `{nmae}`. The
+// key list is checked against `Message::parameter_names`, so nested arguments count.
+#[macro_export]
+macro_rules! format_checked {
+    ($template:expr, locale = $locale:expr $(, $key:expr => $value:expr)* $(,)?) => {
+        $crate::format_checked_keys($template, &[$($key),*], $crate::params!($($key => $value),*), $locale)
+    };
+    ($template:expr $(, $key:expr => $value:expr)* $(,)?) => {
+        $crate::format_checked_keys($template, &[$($key),*], $crate::params!($($key => $value),*), &$crate::Locale::default())
+    };
+}
+
+#[doc(hidden)]
+pub fn format_checked_keys<P: ParameterSource>(
+    message_str: &str,
+    keys: &[&str],
+    parameters: P,
+    locale: &Locale,
+) -> Result<String, MessageFormatError> {
+    let message = parse(message_str)?;
+    if cfg!(debug_assertions) {
+        let names = message.parameter_names();
+        let missing: Vec<&str> = names.iter().map(String::as_str).filter(|name| !keys.contains(name)).collect();
+        let unused: Vec<&str> = keys.iter().copied().filter(|key| !names.contains(*key)).collect();
+        if !missing.is_empty() || !unused.is_empty() {
+            panic!("parameters don't match template {message_str:?}: missing {missing:?}, unused {unused:?}");
+        }
+    }
+    Ok(format_message(&message, parameters, locale)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use icu::locid::locale;
 
     #[test]
     fn test_basic_interpolation() {
@@ -96,9 +208,10 @@ mod tests {
 
     #[test]
     fn test_plural_one_item() {
-        let result = format(
+        let result = format_with_locale(
             "You have {count, plural, one{1 item} other{# items}} in your cart.",
             params!("count" => 1),
+            &locale!("en"),
         );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "You have 1 item in your cart.");
@@ -116,9 +229,10 @@ mod tests {
 
     #[test]
     fn test_plural_zero_items() {
-        let result = format(
+        let result = format_with_locale(
             "{count, plural, zero{No items} one{1 item} other{# items}}",
             params!("count" => 0),
+            &locale!("lv"),
         );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "No items");
@@ -136,7 +250,7 @@ mod tests {
 
     #[test]
     fn test_icu_plural_example() {
-        let result = format("{n, plural, one{# day} other{# days}}", params!("n" => 1));
+        let result = format_with_locale("{n, plural, one{# day} other{# days}}", params!("n" => 1), &locale!("en"));
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "1 day");
     }
@@ -199,15 +313,547 @@ mod tests {
 
     #[test]
     fn test_number_currency_usd() {
-        let result = format("{price, number, currency}", params!("price" => "19.99"));
+        let result = format_with_locale("{price, number, currency}", params!("price" => "19.99"), &locale!("en-US"));
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "$19.99");
     }
 
     #[test]
     fn test_number_currency_eur() {
-        let result = format("{price, number, currency/EUR}", params!("price" => 25));
+        let result = format_with_locale("{price, number, currency/EUR}", params!("price" => 25), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "€25.00");
+    }
+
+    #[test]
+    fn test_format_with_locale_german_number() {
+        let result = format_with_locale("{amount, number}", params!("amount" => "1234.5"), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "1.234,5");
+    }
+
+    #[test]
+    fn test_format_with_locale_german_currency() {
+        let result = format_with_locale("{price, number, currency/EUR}", params!("price" => 25), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "25,00\u{a0}€");
+    }
+
+    #[test]
+    fn test_format_with_locale_inside_plural() {
+        let result = format_with_locale(
+            "{count, plural, one{# item for {price, number}} other{# items for {price, number}}}",
+            params!("count" => 3, "price" => "1234.5"),
+            &locale!("de-DE"),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "3 items for 1.234,5");
+    }
+
+    #[test]
+    fn test_plural_exact_zero_beats_zero_category() {
+        let result = format_with_locale(
+            "{count, plural, zero{Zero items} =0{No items} one{# item} other{# items}}",
+            params!("count" => 0),
+            &locale!("lv"),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "No items");
+    }
+
+    #[test]
+    fn test_plural_zero_category_without_exact_match() {
+        let result = format_with_locale(
+            "{count, plural, =0{No items} zero{# items (zero)} one{# item} other{# items}}",
+            params!("count" => 10),
+            &locale!("lv"),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "10 items (zero)");
+    }
+
+    #[test]
+    fn test_plural_exact_one() {
+        let result = format("{count, plural, =1{Just one} other{# items}}", params!("count" => 1));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Just one");
+    }
+
+    #[test]
+    fn test_plural_quoted_hash_is_literal() {
+        let result = format(
+            "{n, plural, other{Issue '#'{id} has # comments}}",
+            params!("n" => 3, "id" => 5),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Issue #5 has 3 comments");
+    }
+
+    #[test]
+    fn test_hash_outside_plural_is_literal() {
+        let result = format("Issue #{id} is open", params!("id" => 5));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Issue #5 is open");
+    }
+
+    #[test]
+    fn test_hash_in_select_outside_plural_is_literal() {
+        let result = format("{kind, select, other{Ticket #{id}}}", params!("kind" => "bug", "id" => 7));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Ticket #7");
+    }
+
+    #[test]
+    fn test_date_medium_en() {
+        let result = format_with_locale("{d, date, medium}", params!("d" => "2023-06-15"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Jun 15, 2023");
+    }
+
+    #[test]
+    fn test_date_styles_en() {
+        let short = format_with_locale("{d, date, short}", params!("d" => "2023-06-15"), &locale!("en-US"));
+        assert_eq!(short.unwrap(), "6/15/23");
+
+        let long = format_with_locale("{d, date, long}", params!("d" => "2023-06-15"), &locale!("en-US"));
+        assert_eq!(long.unwrap(), "June 15, 2023");
+
+        let full = format_with_locale("{d, date, full}", params!("d" => "2023-06-15"), &locale!("en-US"));
+        assert_eq!(full.unwrap(), "Thursday, June 15, 2023");
+    }
+
+    #[test]
+    fn test_date_long_de() {
+        let result = format_with_locale("{d, date, long}", params!("d" => "2023-06-15T10:30:00"), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "15. Juni 2023");
+    }
+
+    #[test]
+    fn test_date_invalid_value() {
+        let result = format("{d, date}", params!("d" => "not-a-date"));
+        assert!(matches!(
+            result,
+            Err(MessageFormatError::FormatError(FormatError::InvalidParameterType { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_time_short_en_us() {
+        let result = format_with_locale("{t, time, short}", params!("t" => "15:00"), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "3:00\u{202f}PM");
+    }
+
+    #[test]
+    fn test_time_short_de() {
+        let result = format_with_locale("{t, time, short}", params!("t" => "15:00"), &locale!("de-DE"));
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "€25");
+        assert_eq!(result.unwrap(), "15:00");
+    }
+
+    #[test]
+    fn test_time_medium_includes_seconds() {
+        let result = format_with_locale("{t, time, medium}", params!("t" => "2023-06-15T15:04:05"), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "15:04:05");
+    }
+
+    #[test]
+    fn test_time_invalid_value() {
+        let result = format("{t, time}", params!("t" => "25:99"));
+        assert!(matches!(
+            result,
+            Err(MessageFormatError::FormatError(FormatError::InvalidParameterType { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_float_parameter_interpolation() {
+        let result = format_with_locale("Total: {price}", params!("price" => 19.99), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Total: 19.99");
+
+        let result = format_with_locale("Total: {price}", params!("price" => 1234.5), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Total: 1.234,5");
+    }
+
+    #[test]
+    fn test_float_number_currency() {
+        let result = format_with_locale("{price, number, currency}", params!("price" => 19.99), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "$19.99");
+    }
+
+    #[test]
+    fn test_float_plural_whole_value() {
+        let result = format_with_locale("{n, plural, one{# day} other{# days}}", params!("n" => 1.0), &locale!("en"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "1 day");
+    }
+
+    #[test]
+    fn test_float_plural_fraction_uses_rules() {
+        let template = "{n, plural, one{# day} other{# days}}";
+        assert_eq!(format_with_locale(template, params!("n" => 3.0), &locale!("en")).unwrap(), "3 days");
+        assert_eq!(format_with_locale(template, params!("n" => 1.5), &locale!("en")).unwrap(), "1.5 days");
+        // French counts 0 to 2 exclusive as `one`
+        let template = "{n, plural, one{# jour} other{# jours}}";
+        assert_eq!(format_with_locale(template, params!("n" => 1.5), &locale!("fr")).unwrap(), "1,5 jour");
+        assert_eq!(format_with_locale(template, params!("n" => 2.5), &locale!("fr")).unwrap(), "2,5 jours");
+    }
+
+    #[test]
+    fn test_plural_visible_fraction_digits() {
+        let template = "{n, plural, one{# hour} other{# hours}}";
+        assert_eq!(format_with_locale(template, params!("n" => 1), &locale!("en")).unwrap(), "1 hour");
+        // Decimal strings keep their written fraction digits, which CLDR's `v` operand counts
+        assert_eq!(format_with_locale(template, params!("n" => "1.0"), &locale!("en")).unwrap(), "1.0 hours");
+        assert_eq!(format_with_locale(template, params!("n" => "1.00"), &locale!("en")).unwrap(), "1.00 hours");
+        // An `f64` has no written form, so a whole float is a whole number
+        assert_eq!(format_with_locale(template, params!("n" => 1.0), &locale!("en")).unwrap(), "1 hour");
+    }
+
+    #[test]
+    fn test_select_bool_true_false() {
+        let result = format("{flag, select, true{Yes} false{No}}", params!("flag" => true));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Yes");
+
+        let result = format("{flag, select, true{Yes} false{No}}", params!("flag" => false));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "No");
+    }
+
+    #[test]
+    fn test_select_bool_falls_back_to_other() {
+        let result = format("{isAdmin, select, true{Admin} other{User}}", params!("isAdmin" => false));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "User");
+    }
+
+    #[test]
+    fn test_select_bool_string_still_matches() {
+        let result = format("{flag, select, true{Yes} false{No}}", params!("flag" => "true"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Yes");
+    }
+
+    #[test]
+    fn test_parse_error_is_reported() {
+        let result = format("Hello {name", params!("name" => "World"));
+        match result {
+            Err(MessageFormatError::ParseError(err)) => {
+                assert_eq!(err.kind, ParseErrorKind::UnclosedBrace);
+                assert_eq!(err.offset, 6);
+            }
+            other => panic!("Expected parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = format("{count, plural, }", params!("count" => 1)).unwrap_err();
+        assert_eq!(err.to_string(), "Parse error: error at offset 16: expected selector");
+    }
+
+    #[test]
+    fn test_nested_plural_hash_scoping() {
+        let result = format(
+            "{outer, plural, other{# - {inner, plural, other{#}}}}",
+            params!("outer" => 3, "inner" => 7),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "3 - 7");
+    }
+
+    #[test]
+    fn test_nested_plural_hash_after_inner() {
+        let result = format(
+            "{outer, plural, other{{inner, plural, other{# files}} in # folders}}",
+            params!("outer" => 2, "inner" => 9),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "9 files in 2 folders");
+    }
+
+    #[test]
+    fn test_gender_select_wrapping_count_plural() {
+        let template = "{gender, select, \
+            male{He has {count, plural, =0{no photos} one{# photo} other{# photos}}} \
+            female{She has {count, plural, =0{no photos} one{# photo} other{# photos}}} \
+            other{They have {count, plural, =0{no photos} one{# photo} other{# photos}}}}";
+        let message = parse(template).unwrap();
+        let format = |gender: &str, count: i64| {
+            format_message(&message, params!("gender" => gender, "count" => count), &locale!("en")).unwrap()
+        };
+
+        assert_eq!(format("male", 1), "He has 1 photo");
+        assert_eq!(format("male", 1200), "He has 1,200 photos");
+        assert_eq!(format("female", 0), "She has no photos");
+        assert_eq!(format("female", 3), "She has 3 photos");
+        assert_eq!(format("unknown", 1), "They have 1 photo");
+    }
+
+    #[test]
+    fn test_nested_expressions_share_outer_parameter() {
+        // The inner select and the interpolation both read the outer select's value
+        let template = "{role, select, admin{{role} ({role, select, admin{all access} other{limited}})} other{{role}}}";
+        assert_eq!(format(template, params!("role" => "admin")).unwrap(), "admin (all access)");
+        assert_eq!(format(template, params!("role" => "guest")).unwrap(), "guest");
+
+        // A plural nested in a plural of the same count
+        let template = "{n, plural, one{# file} other{{n, plural, =2{both # files} other{all # files}}}}";
+        assert_eq!(format_with_locale(template, params!("n" => 1), &locale!("en")).unwrap(), "1 file");
+        assert_eq!(format_with_locale(template, params!("n" => 2), &locale!("en")).unwrap(), "both 2 files");
+        assert_eq!(format_with_locale(template, params!("n" => 5), &locale!("en")).unwrap(), "all 5 files");
+    }
+
+    #[test]
+    fn test_select_inside_plural_uses_plural_hash() {
+        let result = format_with_locale(
+            "{n, plural, one{{g, select, female{she has # cat} other{they have # cat}}} other{{g, select, female{she has # cats} other{they have # cats}}}}",
+            params!("n" => 4, "g" => "female"),
+            &locale!("en"),
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "she has 4 cats");
+    }
+
+    #[test]
+    fn test_number_fraction_digits_pad() {
+        let result = format_with_locale("{x, number, ::.00}", params!("x" => 19.5), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "19.50");
+    }
+
+    #[test]
+    fn test_number_fraction_digits_round_half_even() {
+        let result = format_with_locale("{x, number, ::.00}", params!("x" => "19.005"), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "19.00");
+
+        let result = format_with_locale("{x, number, ::.00}", params!("x" => "19.015"), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "19.02");
+
+        let result = format_with_locale("{x, number, ::.00}", params!("x" => "19.0051"), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "19.01");
+    }
+
+    #[test]
+    fn test_number_fraction_digits_locale() {
+        let result = format_with_locale("{x, number, ::.00}", params!("x" => 1234.5), &locale!("de-DE"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "1.234,50");
+    }
+
+    #[test]
+    fn test_number_grouping_en_us() {
+        let result = format_with_locale("{n, number}", params!("n" => 1234567), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "1,234,567");
+
+        let result = format_with_locale("{n, number, integer}", params!("n" => "1234567.89"), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "1,234,567");
+    }
+
+    #[test]
+    fn test_number_grouping_de_de() {
+        let result = format_with_locale("{n, number}", params!("n" => 1234567), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1.234.567");
+    }
+
+    #[test]
+    fn test_number_grouping_off() {
+        let result = format_with_locale("{n, number, ::group-off}", params!("n" => 1234567), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1234567");
+    }
+
+    #[test]
+    fn test_number_currency_code_style() {
+        let result = format_with_locale("{price, number, currency/USD/code}", params!("price" => 19.99), &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "USD\u{a0}19.99");
+    }
+
+    #[test]
+    fn test_number_compact() {
+        let result = format_with_locale("{views, number, compact}", params!("views" => 1234), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "1.2K");
+
+        let result = format_with_locale("{views, number, compact}", params!("views" => 3_400_000), &locale!("en-US"));
+        assert_eq!(result.unwrap(), "3.4M");
+    }
+
+    #[test]
+    fn test_number_compact_long_de() {
+        let result = format_with_locale("{views, number, compact-long}", params!("views" => 1_000_000), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1 Million");
+    }
+
+    #[test]
+    fn test_number_compact_skeleton() {
+        let result = format_with_locale("{views, number, ::compact-short}", params!("views" => 1_000_000), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1\u{a0}Mio.");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_format_from_string_map() {
+        let mut values = std::collections::HashMap::new();
+        values.insert("name".to_string(), "World".to_string());
+        values.insert("count".to_string(), "3".to_string());
+
+        let result = format_with_locale("Hello {name}, you have {count, plural, one{# message} other{# messages}}", &values, &locale!("en"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Hello World, you have 3 messages");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_format_from_value_map() {
+        let mut values = std::collections::HashMap::new();
+        values.insert("price".to_string(), ParameterValue::Float(19.99));
+        values.insert("admin".to_string(), ParameterValue::Bool(true));
+
+        let result = format_with_locale("{admin, select, true{Admin} other{User}}: {price, number, currency/USD}", values, &locale!("en-US"));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "Admin: $19.99");
+    }
+
+    #[test]
+    fn test_parse_then_format_repeatedly() {
+        let message = parse("{count, plural, one{# item} other{# items}}").unwrap();
+        assert_eq!(message.parameter_names().len(), 1);
+
+        for (count, expected) in [(1, "1 item"), (2, "2 items")] {
+            let result = format_message(&message, params!("count" => count), &locale!("en"));
+            assert_eq!(result.unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_trailing_input() {
+        let result = parse("Hello {name");
+        match result {
+            Err(MessageFormatError::ParseError(err)) => assert_eq!(err.kind, ParseErrorKind::UnclosedBrace),
+            other => panic!("expected parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_list_format() {
+        let names = vec!["Alice", "Bob", "Carol"];
+        let result = format_with_locale("Invited {names, list}", params!("names" => names), &locale!("en"));
+        assert_eq!(result.unwrap(), "Invited Alice, Bob, and Carol");
+    }
+
+    #[test]
+    fn test_format_from_builder() {
+        let parameters = ParametersBuilder::new()
+            .insert("name", String::from("World"))
+            .insert("count", 2)
+            .build();
+        let result = format_with_locale("Hello {name}, {count, plural, one{# message} other{# messages}}", &parameters, &locale!("en"));
+        assert_eq!(result.unwrap(), "Hello World, 2 messages");
+    }
+
+    #[test]
+    fn test_message_from_str() {
+        let message: Message = "Hello {name}!".parse().unwrap();
+        assert_eq!(message, parse("Hello {name}!").unwrap());
+
+        let error = "Hello {name} and {other".parse::<Message>().unwrap_err();
+        assert_eq!(error.to_string(), "Parse error: error at offset 17: unclosed '{'");
+    }
+
+    #[test]
+    fn test_merged_parameters_override_defaults() {
+        let pairs = [("app", ParameterValue::String("Crate")), ("brand", ParameterValue::String("Platformed"))];
+        let defaults = Parameters::from_slice(&pairs);
+        let result = format("{brand}: welcome to {app}, {name}", defaults.merge(params!("brand" => "Acme", "name" => "Ada")));
+        assert_eq!(result.unwrap(), "Acme: welcome to Crate, Ada");
+
+        let result = format("{brand}", defaults.merge(params!("name" => "Ada")));
+        assert_eq!(result.unwrap(), "Platformed");
+
+        let owned = OwnedParameters::builder().insert("brand", "Owned").build();
+        let result = format("{brand} {app}", (&owned).merge(defaults).merge(&owned));
+        assert_eq!(result.unwrap(), "Owned Crate");
+    }
+
+    #[test]
+    fn test_format_checked() {
+        let result = format_checked!("Hi {name}, {n, plural, one{# {thing}} other{# {thing}s}}", "name" => "Ada", "n" => 2, "thing" => "cat");
+        assert_eq!(result.unwrap(), "Hi Ada, 2 cats");
+
+        let result = format_checked!("{n, number}", locale = &locale!("de"), "n" => 1234.5);
+        assert_eq!(result.unwrap(), "1.234,5");
+
+        assert_eq!(format_checked!("No parameters").unwrap(), "No parameters");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"parameters don't match template "Hi {nmae}": missing ["nmae"], unused ["name"]"#)]
+    fn test_format_checked_reports_typos() {
+        let _ = format_checked!("Hi {nmae}", "name" => "Ada");
+    }
+
+    #[test]
+    fn test_nested_number_uses_outer_locale() {
+        let result = format_with_locale("{g, select, other{{count, number}}}", params!("g" => "x", "count" => 1234567.5), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1.234.567,5");
+
+        let result = format_with_locale("{g, select, other{{count, number}}}", params!("g" => "x", "count" => 1234567.5), &locale!("fr-FR"));
+        assert_eq!(result.unwrap(), "1\u{202f}234\u{202f}567,5");
+    }
+
+    #[test]
+    fn test_plural_case_formats_other_parameter() {
+        let template = "{n, plural, one{# Artikel für {price, number, currency/EUR}} other{# Artikel für je {price, number, currency/EUR}}}";
+        let result = format_with_locale(template, params!("n" => 3, "price" => 1234.56), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "3 Artikel für je 1.234,56\u{a0}€");
+
+        let result = format_with_locale(template, params!("n" => 1, "price" => 5), &locale!("de-DE"));
+        assert_eq!(result.unwrap(), "1 Artikel für 5,00\u{a0}€");
+
+        // The count itself is not read by the inner number
+        let result = format_with_locale(template, params!("n" => 1), &locale!("de-DE"));
+        assert!(matches!(result, Err(MessageFormatError::FormatError(FormatError::MissingParameter(name))) if name == "price"));
+    }
+
+    #[test]
+    fn test_try_from_surfaces_template_errors() {
+        use core::error::Error;
+
+        let error = Message::try_from("Hello {name").unwrap_err();
+        assert!(matches!(&error, MessageFormatError::ParseError(err) if err.kind == ParseErrorKind::UnclosedBrace));
+        let source = error.source().unwrap();
+        assert_eq!(source.to_string(), error.to_string().trim_start_matches("Parse error: "));
+        assert!(source.downcast_ref::<ParseError>().is_some());
+        assert!(source.source().is_none());
+
+        let message = Message::try_from("Hello {name}").unwrap();
+        assert_eq!(format_parsed(&message, params!("name" => "Ada"), &locale!("en")).unwrap(), "Hello Ada");
+        let error = format_parsed(&message, params!(), &locale!("en")).unwrap_err();
+        assert!(error.source().unwrap().downcast_ref::<FormatError>().is_some());
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        let locale = parse_locale("de-DE").unwrap();
+        assert_eq!(locale, locale!("de-DE"));
+        let result = format_with_locale("{n, number}", params!("n" => 1234.5), &locale);
+        assert_eq!(result.unwrap(), "1.234,5");
+        assert_eq!(parse_locale(" en-US ").unwrap(), locale!("en-US"));
+
+        let error = parse_locale("not a locale").unwrap_err();
+        assert!(matches!(error, MessageFormatError::LocaleError(_)));
+        assert!(error.to_string().starts_with("Invalid locale: "));
+    }
+
+    #[test]
+    fn test_comment_produces_no_output() {
+        let result = format_with_locale("Hello{# greeting shown on the home page} world", params!(), &locale!("en"));
+        assert_eq!(result.unwrap(), "Hello world");
     }
 }
@@ -1,17 +1,92 @@
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
-    character::complete::{char, multispace0},
-    combinator::map,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::{char, i64, multispace0, one_of},
+    combinator::{map, opt, recognize, verify},
     multi::{many0, many1},
-    sequence::{delimited, preceded, tuple},
+    error::{Error, ErrorKind},
+    sequence::{delimited, pair, preceded, separated_pair, tuple},
     IResult,
 };
 
-use crate::types::{Message, MessageElement, PluralExpression, PluralCase, PluralSelector, SelectExpression, SelectCase, NumberExpression, NumberFormatType};
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::types::{BorrowedElement, BorrowedMessage, BorrowedPluralCase, BorrowedSelectCase, Message, MessageElement, PluralExpression, PluralCase, PluralSelector, SelectExpression, SelectCase, NumberExpression, NumberFormatType, DateExpression, DateTimeStyle, TimeExpression, ListExpression, ListStyle, CurrencyDisplay, CompactStyle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnclosedBrace,
+    // A `}` with no `{` to close
+    UnmatchedCloseBrace,
+    ExpectedParameterName,
+    // The keyword after the argument name, e.g. `money` in `{amount, money}`
+    UnknownArgumentType(String),
+    InvalidArgumentStyle,
+    ExpectedSelector,
+    ExpectedCaseBody,
+    UnexpectedInput,
+    NestingTooDeep,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnclosedBrace => write!(f, "unclosed '{{'"),
+            ParseErrorKind::UnmatchedCloseBrace => write!(f, "unmatched '}}'; quote it as \"'}}'\" for a literal brace"),
+            ParseErrorKind::ExpectedParameterName => write!(f, "expected argument name"),
+            ParseErrorKind::UnknownArgumentType(keyword) => write!(f, "unknown argument type '{keyword}'"),
+            ParseErrorKind::InvalidArgumentStyle => write!(f, "invalid argument style"),
+            ParseErrorKind::ExpectedSelector => write!(f, "expected selector"),
+            ParseErrorKind::ExpectedCaseBody => write!(f, "expected '{{' to start case"),
+            ParseErrorKind::UnexpectedInput => write!(f, "unexpected input"),
+            ParseErrorKind::NestingTooDeep => write!(f, "case bodies nested too deeply"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // Byte offset into the template where parsing stopped
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+    // The template text starting at `offset`, truncated for display
+    pub snippet: String,
+}
+
+const SNIPPET_LEN: usize = 20;
+
+impl ParseError {
+    fn new(input: &str, offset: usize, kind: ParseErrorKind) -> Self {
+        let snippet = input[offset..].chars().take(SNIPPET_LEN).collect();
+        Self { offset, kind, snippet }
+    }
+
+    // 1-based character column of `offset` within its line
+    pub fn column(&self, input: &str) -> usize {
+        let line_start = input[..self.offset].rfind('\n').map_or(0, |i| i + 1);
+        input[line_start..self.offset].chars().count() + 1
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error at offset {}: {}", self.offset, self.kind)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+// Word characters, with `.` and `-` allowed between them for keys like `user.name`
+fn identifier(input: &str) -> IResult<&str, &str> {
+    let word = || take_while1(|c: char| c.is_alphanumeric() || c == '_');
+    recognize(pair(word(), many0(pair(one_of(".-"), word()))))(input)
+}
 
 fn parameter_name(input: &str) -> IResult<&str, &str> {
-    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+    identifier(input)
 }
 
 fn simple_parameter(input: &str) -> IResult<&str, MessageElement> {
@@ -26,56 +101,194 @@ fn simple_parameter(input: &str) -> IResult<&str, MessageElement> {
 }
 
 fn plural_selector(input: &str) -> IResult<&str, PluralSelector> {
-    map(
-        take_while1(|c: char| c.is_alphanumeric()),
-        |s: &str| PluralSelector::parse(s).unwrap_or(PluralSelector::Other),
-    )(input)
+    alt((
+        map(preceded(char('='), i64), PluralSelector::Exact),
+        plural_range,
+        map(
+            take_while1(|c: char| c.is_alphanumeric()),
+            |s: &str| PluralSelector::parse(s).unwrap_or(PluralSelector::Other),
+        ),
+    ))(input)
 }
 
 
-fn case_content(input: &str) -> IResult<&str, Message> {
+fn plural_range(input: &str) -> IResult<&str, PluralSelector> {
+    let (rest, (start, end)) = separated_pair(i64, tag(".."), i64)(input)?;
+    if start > end {
+        // A failure, so the bounds aren't read again as a category name
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)));
+    }
+    Ok((rest, PluralSelector::Range(start, end)))
+}
+
+// Maximum number of nested case bodies accepted by `parse_message_complete`
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+// Case bodies recurse through the parser, so each parser that can reach one takes the
+// number of case bodies that may still be opened inside it
+fn enter_case(input: &str, depth: usize) -> Result<usize, nom::Err<Error<&str>>> {
+    depth.checked_sub(1).ok_or(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)))
+}
+
+// `{# note}` is a translator comment: everything up to the next `}` is discarded and
+// produces no element, so comments can't contain `}`
+fn comment(input: &str) -> IResult<&str, ()> {
+    map(delimited(tag("{#"), take_while(|c: char| c != '}'), char('}')), |_| ())(input)
+}
+
+// Zero or more elements, skipping comments between them
+fn elements<'a, O>(element: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    map(many0(alt((map(comment, |_| None), map(element, Some)))), |elements| elements.into_iter().flatten().collect())
+}
+
+fn case_content(input: &str, depth: usize) -> IResult<&str, Message> {
+    let depth = enter_case(input, depth)?;
     delimited(
         char('{'),
-        map(many0(alt((number_expression, select_expression, plural_expression, simple_parameter, text_segment_in_case))), Message::new),
+        map(elements(|i| case_element(i, depth)), Message::new),
         char('}'),
     )(input)
 }
 
+fn case_element(input: &str, depth: usize) -> IResult<&str, MessageElement> {
+    alt((number_expression, date_expression, time_expression, list_expression, |i| select_expression(i, depth), |i| plural_expression(i, depth), simple_parameter, plural_hash, text_segment_in_case))(input)
+}
+
 fn text_segment_in_case(input: &str) -> IResult<&str, MessageElement> {
-    map(
-        take_while1(|c: char| c != '{' && c != '}'),
-        |text: &str| MessageElement::Text(text.to_string()),
-    )(input)
+    map(|i| escaped_text(i, &['{', '}', '#']), MessageElement::Text)(input)
+}
+
+fn plural_hash(input: &str) -> IResult<&str, MessageElement> {
+    map(char('#'), |_| MessageElement::PluralHash)(input)
+}
+
+fn is_syntax_char(c: char) -> bool {
+    matches!(c, '{' | '}' | '#')
+}
+
+// Consumes literal text up to the first unquoted stop character, applying ICU
+// apostrophe rules: `''` is a literal apostrophe, and an apostrophe followed by
+// a syntax character starts a quoted run that ends at the next lone apostrophe.
+// Any other apostrophe (including a trailing one) is kept as-is.
+fn escaped_text<'a>(input: &'a str, stop: &[char]) -> IResult<&'a str, String> {
+    let mut text = String::new();
+    let mut chars = input.char_indices().peekable();
+    let mut end = input.len();
+
+    while let Some((i, c)) = chars.next() {
+        if stop.contains(&c) {
+            end = i;
+            break;
+        }
+        if c != '\'' {
+            text.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some((_, '\'')) => {
+                chars.next();
+                text.push('\'');
+            }
+            Some((_, next)) if is_syntax_char(*next) => {
+                while let Some((_, quoted)) = chars.next() {
+                    if quoted == '\'' {
+                        if let Some((_, '\'')) = chars.peek() {
+                            chars.next();
+                            text.push('\'');
+                        } else {
+                            break;
+                        }
+                    } else {
+                        text.push(quoted);
+                    }
+                }
+            }
+            _ => text.push('\''),
+        }
+    }
+
+    if end == 0 {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::TakeWhile1)));
+    }
+    Ok((&input[end..], text))
 }
 
-fn plural_case(input: &str) -> IResult<&str, PluralCase> {
+// Whitespace around selectors and between cases is insignificant, but the text
+// inside a case body is kept verbatim, including leading and trailing spaces.
+// `FormatOptions::trim_case_whitespace` trims it at format time instead.
+fn plural_case(input: &str, depth: usize) -> IResult<&str, PluralCase> {
     map(
         tuple((
             delimited(multispace0, plural_selector, multispace0),
-            case_content,
+            |i| case_content(i, depth),
         )),
         |(selector, message)| PluralCase { selector, message },
     )(input)
 }
 
-fn select_case(input: &str) -> IResult<&str, SelectCase> {
+fn select_case(input: &str, depth: usize) -> IResult<&str, SelectCase> {
     map(
         tuple((
             delimited(multispace0, select_selector, multispace0),
-            case_content,
+            |i| case_content(i, depth),
         )),
         |(selector, message)| SelectCase { selector, message },
     )(input)
 }
 
 fn select_selector(input: &str) -> IResult<&str, String> {
-    map(
-        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
-        |s: &str| s.to_string(),
-    )(input)
+    alt((quoted_selector, map(identifier, |s: &str| s.to_string())))(input)
+}
+
+// `'a, b'` allows any characters up to the closing apostrophe; `''` inside is a
+// literal apostrophe
+fn quoted_selector(input: &str) -> IResult<&str, String> {
+    let Some(body) = input.strip_prefix('\'') else {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::Char)));
+    };
+    let mut selector = String::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\'' {
+            selector.push(c);
+        } else if let Some((_, '\'')) = chars.peek() {
+            chars.next();
+            selector.push('\'');
+        } else if selector.is_empty() {
+            break;
+        } else {
+            return Ok((&body[i + 1..], selector));
+        }
+    }
+    Err(nom::Err::Error(Error::new(input, ErrorKind::Char)))
+}
+
+// `gender` is accepted as another name for `select`, with the same cases
+fn select_keyword(input: &str) -> IResult<&str, &str> {
+    alt((tag("select"), tag("gender")))(input)
+}
+
+// An optional number skeleton before the first case formats `#`, as in
+// `{n, plural, ::compact-short other{# views}}`. It runs up to the first token that
+// is followed by `{`, which is the first selector.
+fn plural_hash_skeleton(input: &str) -> IResult<&str, &str> {
+    let (body, _) = tag("::")(input)?;
+    let mut end = 0;
+    loop {
+        let rest = body[end..].trim_start();
+        let token_len = rest.find(|c: char| c.is_whitespace() || c == '{' || c == '}').unwrap_or(rest.len());
+        if token_len == 0 || rest[token_len..].trim_start().starts_with('{') {
+            break;
+        }
+        end = body.len() - rest.len() + token_len;
+    }
+    if end == 0 {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+    }
+    Ok((&body[end..], body[..end].trim_start()))
 }
 
-fn plural_expression(input: &str) -> IResult<&str, MessageElement> {
+fn plural_expression(input: &str, depth: usize) -> IResult<&str, MessageElement> {
     map(
         delimited(
             char('{'),
@@ -83,29 +296,33 @@ fn plural_expression(input: &str) -> IResult<&str, MessageElement> {
                 delimited(multispace0, parameter_name, multispace0),
                 preceded(
                     tuple((char(','), multispace0, tag("plural"), multispace0, char(','))),
-                    delimited(multispace0, many1(plural_case), multispace0),
+                    tuple((
+                        preceded(multispace0, opt(plural_hash_skeleton)),
+                        delimited(multispace0, many1(|i| plural_case(i, depth)), multispace0),
+                    )),
                 ),
             )),
             char('}'),
         ),
-        |(param, cases)| {
+        |(param, (hash_skeleton, cases))| {
             MessageElement::Plural(PluralExpression {
                 parameter: param.to_string(),
                 cases,
+                hash_skeleton: hash_skeleton.map(str::to_string),
             })
         },
     )(input)
 }
 
-fn select_expression(input: &str) -> IResult<&str, MessageElement> {
+fn select_expression(input: &str, depth: usize) -> IResult<&str, MessageElement> {
     map(
         delimited(
             char('{'),
             tuple((
                 delimited(multispace0, parameter_name, multispace0),
                 preceded(
-                    tuple((char(','), multispace0, tag("select"), multispace0, char(','))),
-                    delimited(multispace0, many1(select_case), multispace0),
+                    tuple((char(','), multispace0, select_keyword, multispace0, char(','))),
+                    delimited(multispace0, many1(|i| select_case(i, depth)), multispace0),
                 ),
             )),
             char('}'),
@@ -128,9 +345,10 @@ fn number_expression(input: &str) -> IResult<&str, MessageElement> {
                 preceded(
                     tuple((char(','), multispace0, tag("number"))),
                     alt((
-                        preceded(
+                        delimited(
                             tuple((multispace0, char(','), multispace0)),
                             number_format_type,
+                            multispace0,
                         ),
                         map(multispace0, |_| NumberFormatType::Number),
                     )),
@@ -149,38 +367,403 @@ fn number_expression(input: &str) -> IResult<&str, MessageElement> {
 
 fn number_format_type(input: &str) -> IResult<&str, NumberFormatType> {
     alt((
+        map(
+            preceded(tag("::"), take_while1(|c: char| c != '}')),
+            |skeleton: &str| NumberFormatType::Skeleton(skeleton.trim().to_string()),
+        ),
         map(tag("integer"), |_| NumberFormatType::Integer),
         map(tag("percent"), |_| NumberFormatType::Percent),
+        map(tag("spellout"), |_| NumberFormatType::Spellout),
+        map(tag("ordinal"), |_| NumberFormatType::Ordinal),
+        map(tag("scientific"), |_| NumberFormatType::Scientific),
+        map(tag("engineering"), |_| NumberFormatType::Engineering),
+        map(tag("compact-long"), |_| NumberFormatType::Compact(CompactStyle::Long)),
+        map(alt((tag("compact-short"), tag("compact"))), |_| NumberFormatType::Compact(CompactStyle::Short)),
         map(
             preceded(tag("currency"),
-                alt((
-                    preceded(char('/'), map(take_while1(|c: char| c.is_alphanumeric()), |s: &str| s.to_string())),
-                    map(tag(""), |_| "USD".to_string()),
+                tuple((
+                    alt((
+                        preceded(char('/'), map(alt((recognize(pair(char('$'), identifier)), currency_code)), |s: &str| Some(s.to_string()))),
+                        // No code: the formatter uses the locale's currency
+                        map(tag(""), |_| None),
+                    )),
+                    alt((
+                        preceded(char('/'), currency_display),
+                        map(tag(""), |_| CurrencyDisplay::Symbol),
+                    )),
                 ))
             ),
-            NumberFormatType::Currency,
+            |(code, display)| NumberFormatType::Currency(code, display),
         ),
         map(tag(""), |_| NumberFormatType::Number),
     ))(input)
 }
 
+fn date_expression(input: &str) -> IResult<&str, MessageElement> {
+    map(date_time_argument("date"), |(param, style)| {
+        MessageElement::Date(DateExpression {
+            parameter: param.to_string(),
+            style,
+        })
+    })(input)
+}
+
+fn time_expression(input: &str) -> IResult<&str, MessageElement> {
+    map(date_time_argument("time"), |(param, style)| {
+        MessageElement::Time(TimeExpression {
+            parameter: param.to_string(),
+            style,
+        })
+    })(input)
+}
+
+// `{param, <keyword>}` or `{param, <keyword>, <style>}`, defaulting to medium
+fn date_time_argument<'a>(keyword: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, DateTimeStyle)> {
+    delimited(
+        char('{'),
+        tuple((
+            delimited(multispace0, parameter_name, multispace0),
+            preceded(
+                tuple((char(','), multispace0, tag(keyword))),
+                alt((
+                    delimited(
+                        tuple((multispace0, char(','), multispace0)),
+                        date_time_style,
+                        multispace0,
+                    ),
+                    map(multispace0, |_| DateTimeStyle::Medium),
+                )),
+            ),
+        )),
+        char('}'),
+    )
+}
+
+// `{param, list}` or `{param, list, <style>}`, defaulting to `and`
+fn list_expression(input: &str) -> IResult<&str, MessageElement> {
+    map(
+        delimited(
+            char('{'),
+            tuple((
+                delimited(multispace0, parameter_name, multispace0),
+                preceded(
+                    tuple((char(','), multispace0, tag("list"))),
+                    alt((
+                        delimited(
+                            tuple((multispace0, char(','), multispace0)),
+                            list_style,
+                            multispace0,
+                        ),
+                        map(multispace0, |_| ListStyle::And),
+                    )),
+                ),
+            )),
+            char('}'),
+        ),
+        |(param, style)| {
+            MessageElement::List(ListExpression {
+                parameter: param.to_string(),
+                style,
+            })
+        },
+    )(input)
+}
+
+fn list_style(input: &str) -> IResult<&str, ListStyle> {
+    alt((
+        map(tag("and"), |_| ListStyle::And),
+        map(tag("or"), |_| ListStyle::Or),
+        map(tag("unit"), |_| ListStyle::Unit),
+    ))(input)
+}
+
+fn date_time_style(input: &str) -> IResult<&str, DateTimeStyle> {
+    alt((
+        map(tag("short"), |_| DateTimeStyle::Short),
+        map(tag("medium"), |_| DateTimeStyle::Medium),
+        map(tag("long"), |_| DateTimeStyle::Long),
+        map(tag("full"), |_| DateTimeStyle::Full),
+    ))(input)
+}
+
+// A display keyword after a bare `currency` is not a code
+fn currency_code(input: &str) -> IResult<&str, &str> {
+    verify(take_while1(|c: char| c.is_alphanumeric()), |code: &str| {
+        !matches!(code, "symbol" | "narrow" | "code")
+    })(input)
+}
+
+fn currency_display(input: &str) -> IResult<&str, CurrencyDisplay> {
+    alt((
+        map(tag("symbol"), |_| CurrencyDisplay::Symbol),
+        map(tag("narrow"), |_| CurrencyDisplay::NarrowSymbol),
+        map(tag("code"), |_| CurrencyDisplay::Code),
+    ))(input)
+}
+
+// A `}` outside any argument is an error; quote it as `'}'` to use it literally
 fn text_segment(input: &str) -> IResult<&str, MessageElement> {
+    map(|i| escaped_text(i, &['{', '}']), MessageElement::Text)(input)
+}
+
+fn message_element(input: &str, depth: usize) -> IResult<&str, MessageElement> {
+    alt((number_expression, date_expression, time_expression, list_expression, |i| select_expression(i, depth), |i| plural_expression(i, depth), simple_parameter, text_segment))(input)
+}
+
+// The borrowed grammar mirrors the owned one above; only text, parameters, plurals
+// and selects have borrowed forms, other arguments reuse the owned parsers
+fn borrowed_element(input: &str, depth: usize) -> IResult<&str, BorrowedElement<'_>> {
+    alt((borrowed_argument, |i| borrowed_select(i, depth), |i| borrowed_plural(i, depth), borrowed_parameter, |i| borrowed_text(i, &['{', '}'])))(input)
+}
+
+fn borrowed_case_element(input: &str, depth: usize) -> IResult<&str, BorrowedElement<'_>> {
+    alt((
+        borrowed_argument,
+        |i| borrowed_select(i, depth),
+        |i| borrowed_plural(i, depth),
+        borrowed_parameter,
+        map(char('#'), |_| BorrowedElement::PluralHash),
+        |i| borrowed_text(i, &['{', '}', '#']),
+    ))(input)
+}
+
+fn borrowed_argument(input: &str) -> IResult<&str, BorrowedElement<'_>> {
+    map(alt((number_expression, date_expression, time_expression, list_expression)), BorrowedElement::Argument)(input)
+}
+
+fn borrowed_parameter(input: &str) -> IResult<&str, BorrowedElement<'_>> {
     map(
-        take_while1(|c: char| c != '{'),
-        |text: &str| MessageElement::Text(text.to_string()),
+        delimited(char('{'), delimited(multispace0, parameter_name, multispace0), char('}')),
+        BorrowedElement::Parameter,
+    )(input)
+}
+
+// Text without apostrophes is sliced from the template; escapes need unquoting
+fn borrowed_text<'a>(input: &'a str, stop: &[char]) -> IResult<&'a str, BorrowedElement<'a>> {
+    let end = input.find(|c: char| c == '\'' || stop.contains(&c)).unwrap_or(input.len());
+    if input[end..].starts_with('\'') {
+        return map(|i| escaped_text(i, stop), |text| BorrowedElement::Text(Cow::Owned(text)))(input);
+    }
+    if end == 0 {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::TakeWhile1)));
+    }
+    Ok((&input[end..], BorrowedElement::Text(Cow::Borrowed(&input[..end]))))
+}
+
+fn borrowed_case_content(input: &str, depth: usize) -> IResult<&str, BorrowedMessage<'_>> {
+    let depth = enter_case(input, depth)?;
+    delimited(
+        char('{'),
+        map(elements(|i| borrowed_case_element(i, depth)), |elements| BorrowedMessage { elements }),
+        char('}'),
+    )(input)
+}
+
+fn borrowed_plural(input: &str, depth: usize) -> IResult<&str, BorrowedElement<'_>> {
+    let case = map(
+        tuple((delimited(multispace0, plural_selector, multispace0), |i| borrowed_case_content(i, depth))),
+        |(selector, message)| BorrowedPluralCase { selector, message },
+    );
+    map(
+        delimited(
+            char('{'),
+            tuple((
+                delimited(multispace0, parameter_name, multispace0),
+                preceded(
+                    tuple((char(','), multispace0, tag("plural"), multispace0, char(','))),
+                    tuple((
+                        preceded(multispace0, opt(plural_hash_skeleton)),
+                        delimited(multispace0, many1(case), multispace0),
+                    )),
+                ),
+            )),
+            char('}'),
+        ),
+        |(parameter, (hash_skeleton, cases))| BorrowedElement::Plural { parameter, hash_skeleton, cases },
     )(input)
 }
 
-fn message_element(input: &str) -> IResult<&str, MessageElement> {
-    alt((number_expression, select_expression, plural_expression, simple_parameter, text_segment))(input)
+fn borrowed_select(input: &str, depth: usize) -> IResult<&str, BorrowedElement<'_>> {
+    let selector = alt((map(quoted_selector, Cow::Owned), map(identifier, Cow::Borrowed)));
+    let case = map(
+        tuple((delimited(multispace0, selector, multispace0), |i| borrowed_case_content(i, depth))),
+        |(selector, message)| BorrowedSelectCase { selector, message },
+    );
+    map(
+        delimited(
+            char('{'),
+            tuple((
+                delimited(multispace0, parameter_name, multispace0),
+                preceded(
+                    tuple((char(','), multispace0, select_keyword, multispace0, char(','))),
+                    delimited(multispace0, many1(case), multispace0),
+                ),
+            )),
+            char('}'),
+        ),
+        |(parameter, cases)| BorrowedElement::Select { parameter, cases },
+    )(input)
 }
 
 pub fn parse_message(input: &str) -> IResult<&str, Message> {
-    map(many0(message_element), |elements| {
+    parse_message_to_depth(input, DEFAULT_MAX_DEPTH)
+}
+
+fn parse_message_to_depth(input: &str, max_depth: usize) -> IResult<&str, Message> {
+    map(elements(|i| message_element(i, max_depth)), |elements| {
         Message::new(elements)
     })(input)
 }
 
+// Parses the whole template, failing if any input is left unconsumed
+pub fn parse_message_complete(input: &str) -> Result<Message, ParseError> {
+    parse_message_with_max_depth(input, DEFAULT_MAX_DEPTH)
+}
+
+// Like `parse_message_complete`, with a custom limit on nested case bodies
+pub fn parse_message_with_max_depth(input: &str, max_depth: usize) -> Result<Message, ParseError> {
+    parse_complete(input, |i| parse_message_to_depth(i, max_depth))
+}
+
+// Like `parse_message_complete`, but text, names and selectors borrow from `input`
+pub fn parse_message_borrowed(input: &str) -> Result<BorrowedMessage<'_>, ParseError> {
+    parse_complete(input, |i| {
+        map(elements(|i| borrowed_element(i, DEFAULT_MAX_DEPTH)), |elements| BorrowedMessage { elements })(i)
+    })
+}
+
+fn parse_complete<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, ParseError> {
+    match parser(input) {
+        Ok(("", message)) => Ok(message),
+        Ok((remaining, _)) => Err(diagnose(input, remaining)),
+        Err(nom::Err::Failure(err)) if err.code == ErrorKind::TooLarge => {
+            Err(ParseError::new(input, input.len() - err.input.len(), ParseErrorKind::NestingTooDeep))
+        }
+        Err(_) => Err(diagnose(input, input)),
+    }
+}
+
+// Explains why `parse_message` stopped before the end of `input`. `remaining` is
+// the unconsumed suffix, which starts at an argument that failed to parse or at a
+// stray `}`.
+fn diagnose(input: &str, remaining: &str) -> ParseError {
+    let offset = input.len() - remaining.len();
+    if remaining.starts_with('{') {
+        diagnose_argument(input, offset)
+    } else if remaining.starts_with('}') {
+        ParseError::new(input, offset, ParseErrorKind::UnmatchedCloseBrace)
+    } else {
+        ParseError::new(input, offset, ParseErrorKind::UnexpectedInput)
+    }
+}
+
+fn skip_whitespace(input: &str, pos: usize) -> usize {
+    pos + (input[pos..].len() - input[pos..].trim_start().len())
+}
+
+fn diagnose_argument(input: &str, start: usize) -> ParseError {
+    let error = |offset, kind| ParseError::new(input, offset, kind);
+    let unclosed = || ParseError::new(input, start, ParseErrorKind::UnclosedBrace);
+
+    if input[start + 1..].starts_with('#') {
+        return unclosed();
+    }
+
+    let mut pos = skip_whitespace(input, start + 1);
+    match parameter_name(&input[pos..]) {
+        Ok((_, name)) => pos += name.len(),
+        Err(_) if pos == input.len() => return unclosed(),
+        Err(_) => return error(pos, ParseErrorKind::ExpectedParameterName),
+    }
+
+    pos = skip_whitespace(input, pos);
+    match input[pos..].chars().next() {
+        None => return unclosed(),
+        Some(',') => pos = skip_whitespace(input, pos + 1),
+        Some(_) => return error(pos, ParseErrorKind::UnexpectedInput),
+    }
+
+    let keyword_len = input[pos..].chars().take_while(|c| c.is_alphanumeric()).count();
+    let keyword = &input[pos..pos + keyword_len];
+    match keyword {
+        "plural" | "select" | "gender" => diagnose_cases(input, start, pos + keyword_len, keyword == "plural"),
+        "number" | "date" | "time" | "list" => {
+            pos = skip_whitespace(input, pos + keyword_len);
+            match input[pos..].chars().next() {
+                None => unclosed(),
+                Some(',') => {
+                    pos = skip_whitespace(input, pos + 1);
+                    if input[pos..].contains('}') {
+                        error(pos, ParseErrorKind::InvalidArgumentStyle)
+                    } else {
+                        unclosed()
+                    }
+                }
+                Some(_) => error(pos, ParseErrorKind::UnexpectedInput),
+            }
+        }
+        _ if pos == input.len() => unclosed(),
+        _ => error(pos, ParseErrorKind::UnknownArgumentType(keyword.to_string())),
+    }
+}
+
+fn diagnose_cases(input: &str, start: usize, mut pos: usize, plural: bool) -> ParseError {
+    let error = |offset, kind| ParseError::new(input, offset, kind);
+    let unclosed = |offset| ParseError::new(input, offset, ParseErrorKind::UnclosedBrace);
+
+    pos = skip_whitespace(input, pos);
+    match input[pos..].chars().next() {
+        None => return unclosed(start),
+        Some(',') => pos += 1,
+        Some(_) => return error(pos, ParseErrorKind::UnexpectedInput),
+    }
+
+    if plural {
+        pos = skip_whitespace(input, pos);
+        if let Ok((rest, _)) = plural_hash_skeleton(&input[pos..]) {
+            pos = input.len() - rest.len();
+        }
+    }
+
+    let mut case_count = 0;
+    loop {
+        pos = skip_whitespace(input, pos);
+        let selector_len = match input[pos..].chars().next() {
+            None => return unclosed(start),
+            Some('}') if case_count > 0 => return error(pos, ParseErrorKind::UnexpectedInput),
+            Some(_) if plural => plural_selector(&input[pos..]).map(|(rest, _)| input.len() - pos - rest.len()),
+            Some(_) => select_selector(&input[pos..]).map(|(rest, _)| input.len() - pos - rest.len()),
+        };
+        match selector_len {
+            Ok(len) => pos += len,
+            Err(_) => return error(pos, ParseErrorKind::ExpectedSelector),
+        }
+
+        pos = skip_whitespace(input, pos);
+        match input[pos..].chars().next() {
+            None => return unclosed(start),
+            Some('{') => {}
+            Some(_) => return error(pos, ParseErrorKind::ExpectedCaseBody),
+        }
+
+        let body_start = pos;
+        let rest = match elements(|i| case_element(i, DEFAULT_MAX_DEPTH))(&input[pos + 1..]) {
+            Ok((rest, _)) => rest,
+            Err(_) => return error(pos + 1, ParseErrorKind::UnexpectedInput),
+        };
+        pos = input.len() - rest.len();
+        match rest.chars().next() {
+            None => return unclosed(body_start),
+            Some('}') => pos += 1,
+            Some(_) => return diagnose_argument(input, pos),
+        }
+        case_count += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +786,33 @@ mod tests {
         assert_eq!(message.elements[0], MessageElement::Parameter("name".to_string()));
     }
 
+    #[test]
+    fn test_parse_dotted_and_hyphenated_names() {
+        let (remaining, message) = parse_message("{user.name} has {a-b}").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(message.elements, vec![
+            MessageElement::Parameter("user.name".to_string()),
+            MessageElement::Text(" has ".to_string()),
+            MessageElement::Parameter("a-b".to_string()),
+        ]);
+
+        let message = parse_message_complete("{user.role, select, site-admin{A} other{B}}").unwrap();
+        match &message.elements[0] {
+            MessageElement::Select(select_expr) => {
+                assert_eq!(select_expr.parameter, "user.role");
+                assert_eq!(select_expr.cases[0].selector, "site-admin");
+            }
+            other => panic!("expected select, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_leading_or_trailing_separator() {
+        assert!(parse_message_complete("{.name}").is_err());
+        assert!(parse_message_complete("{name-}").is_err());
+        assert!(parse_message_complete("{a..b}").is_err());
+    }
+
     #[test]
     fn test_parse_mixed_content() {
         let result = parse_message("Hello {name}!");
@@ -243,8 +853,9 @@ mod tests {
             assert_eq!(plural_expr.cases[0].message.elements[0], MessageElement::Text("1 item".to_string()));
 
             assert_eq!(plural_expr.cases[1].selector, PluralSelector::Other);
-            assert_eq!(plural_expr.cases[1].message.elements.len(), 1);
-            assert_eq!(plural_expr.cases[1].message.elements[0], MessageElement::Text("# items".to_string()));
+            assert_eq!(plural_expr.cases[1].message.elements.len(), 2);
+            assert_eq!(plural_expr.cases[1].message.elements[0], MessageElement::PluralHash);
+            assert_eq!(plural_expr.cases[1].message.elements[1], MessageElement::Text(" items".to_string()));
         } else {
             panic!("Expected plural expression");
         }
@@ -287,6 +898,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_gender_as_select() {
+        let template = "{g, gender, masculine{He} feminine{She} neuter{It} other{They}}";
+        let message = parse_message_complete(template).unwrap();
+        let expected = parse_message_complete("{g, select, masculine{He} feminine{She} neuter{It} other{They}}").unwrap();
+        assert_eq!(message, expected);
+        assert_eq!(parse_message_borrowed(template).unwrap().into_owned(), expected);
+
+        let error = parse_message_complete("{g, gender, masculine He}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ExpectedCaseBody);
+    }
+
     #[test]
     fn test_parse_number_basic() {
         let result = parse_message("{count, number}");
@@ -326,9 +949,502 @@ mod tests {
 
         if let MessageElement::Number(number_expr) = &message.elements[0] {
             assert_eq!(number_expr.parameter, "price");
-            assert_eq!(number_expr.format_type, NumberFormatType::Currency("EUR".to_string()));
+            assert_eq!(number_expr.format_type, NumberFormatType::Currency(Some("EUR".to_string()), CurrencyDisplay::Symbol));
         } else {
             panic!("Expected number expression");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_plural_hash_skeleton() {
+        let message = parse_message_complete("{n, plural, ::compact-short group-off one {# view} other{# views}}").unwrap();
+        let MessageElement::Plural(plural_expr) = &message.elements[0] else { panic!("expected a plural") };
+        assert_eq!(plural_expr.hash_skeleton.as_deref(), Some("compact-short group-off"));
+        assert_eq!(plural_expr.cases.len(), 2);
+        assert_eq!(message.to_string(), "{n, plural, ::compact-short group-off one{# view} other{# views}}");
+
+        let message = parse_message_complete("{n, plural, :: percent =0{none} other{#}}").unwrap();
+        let MessageElement::Plural(plural_expr) = &message.elements[0] else { panic!("expected a plural") };
+        assert_eq!(plural_expr.hash_skeleton.as_deref(), Some("percent"));
+        assert_eq!(plural_expr.cases[0].selector, PluralSelector::Exact(0));
+        let borrowed = parse_message_borrowed("{n, plural, :: percent =0{none} other{#}}").unwrap();
+        assert_eq!(borrowed.into_owned(), message);
+
+        // Errors after the skeleton are still located in the cases
+        let error = parse_message_complete("{n, plural, ::percent one{#} other{# }").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnclosedBrace);
+        assert_eq!(error.offset, 0);
+        let error = parse_message_complete("{n, plural, ::percent}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ExpectedSelector);
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let expected = parse_message_complete("Hello {name}, bye").unwrap();
+        let message = parse_message_complete("Hello {# the user's first name}{name}{#}, bye").unwrap();
+        assert_eq!(message.elements[1..], expected.elements[1..]);
+        assert_eq!(message.to_string(), "Hello {name}, bye");
+
+        let message = parse_message_complete("{n, plural, one{# {#count} item} other{#}}").unwrap();
+        assert_eq!(message.to_string(), "{n, plural, one{#  item} other{#}}");
+        let borrowed = parse_message_borrowed("a{# note}b").unwrap();
+        assert_eq!(borrowed.into_owned().to_string(), "ab");
+
+        let error = parse_message_complete("Hi {# unclosed").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnclosedBrace);
+        assert_eq!(error.offset, 3);
+    }
+
+    #[test]
+    fn test_parse_empty_case_bodies() {
+        let message = parse_message_complete("{count, plural, =0{} other{# items}}{g, select, male{} other{}}").unwrap();
+        let MessageElement::Plural(plural_expr) = &message.elements[0] else {
+            panic!("Expected plural expression");
+        };
+        assert_eq!(plural_expr.cases[0].message, Message::new(vec![]));
+        let MessageElement::Select(select_expr) = &message.elements[1] else {
+            panic!("Expected select expression");
+        };
+        assert!(select_expr.cases.iter().all(|case| case.message.elements.is_empty()));
+
+        let borrowed = parse_message_borrowed("{count, plural, =0{} other{# items}}").unwrap();
+        assert_eq!(borrowed.into_owned(), parse_message_complete("{count, plural, =0{} other{# items}}").unwrap());
+    }
+
+    #[test]
+    fn test_parse_plural_range_selectors() {
+        let message = parse_message_complete("{n, plural, 2..4{few} -1..0{low} other{#}}").unwrap();
+        let MessageElement::Plural(plural_expr) = &message.elements[0] else {
+            panic!("Expected plural expression");
+        };
+        assert_eq!(plural_expr.cases[0].selector, PluralSelector::Range(2, 4));
+        assert_eq!(plural_expr.cases[1].selector, PluralSelector::Range(-1, 0));
+
+        let error = parse_message_complete("{n, plural, 4..2{backwards} other{#}}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ExpectedSelector);
+        assert_eq!(error.offset, 12);
+    }
+
+    #[test]
+    fn test_parse_plural_exact_selectors() {
+        let result = parse_message("{count, plural, =0{none} zero{zero} =1{one} other{#}}");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "");
+
+        if let MessageElement::Plural(plural_expr) = &message.elements[0] {
+            assert_eq!(plural_expr.cases.len(), 4);
+            assert_eq!(plural_expr.cases[0].selector, PluralSelector::Exact(0));
+            assert_eq!(plural_expr.cases[1].selector, PluralSelector::Zero);
+            assert_eq!(plural_expr.cases[2].selector, PluralSelector::Exact(1));
+            assert_eq!(plural_expr.cases[3].selector, PluralSelector::Other);
+        } else {
+            panic!("Expected plural expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_apostrophe_escaped_braces() {
+        let result = parse_message("It's a '{'literal'}'");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(message.elements, vec![MessageElement::Text("It's a {literal}".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_apostrophe_quoted_run() {
+        let result = parse_message("'{name}' is {name}");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(message.elements.len(), 2);
+        assert_eq!(message.elements[0], MessageElement::Text("{name} is ".to_string()));
+        assert_eq!(message.elements[1], MessageElement::Parameter("name".to_string()));
+    }
+
+    #[test]
+    fn test_parse_doubled_apostrophe() {
+        let result = parse_message("I''m here, '{''}'");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(message.elements, vec![MessageElement::Text("I'm here, {'}".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_trailing_apostrophe_is_literal() {
+        let result = parse_message("the students'");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(message.elements, vec![MessageElement::Text("the students'".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_apostrophe_escape_in_case() {
+        let result = parse_message("{gender, select, other{'{'braces'}' aren''t special}}");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "");
+
+        if let MessageElement::Select(select_expr) = &message.elements[0] {
+            assert_eq!(
+                select_expr.cases[0].message.elements,
+                vec![MessageElement::Text("{braces} aren't special".to_string())]
+            );
+        } else {
+            panic!("Expected select expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_quoted_hash_in_plural() {
+        let result = parse_message("{n, plural, other{'#'# left}}");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+
+        if let MessageElement::Plural(plural_expr) = &message.elements[0] {
+            assert_eq!(
+                plural_expr.cases[0].message.elements,
+                vec![
+                    MessageElement::Text("#".to_string()),
+                    MessageElement::PluralHash,
+                    MessageElement::Text(" left".to_string()),
+                ]
+            );
+        } else {
+            panic!("Expected plural expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_date_styles() {
+        let result = parse_message("{when, date, long}");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(
+            message.elements[0],
+            MessageElement::Date(DateExpression {
+                parameter: "when".to_string(),
+                style: DateTimeStyle::Long,
+            })
+        );
+
+        let result = parse_message("{when, date}");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(
+            message.elements[0],
+            MessageElement::Date(DateExpression {
+                parameter: "when".to_string(),
+                style: DateTimeStyle::Medium,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let result = parse_message("{t, time, short}");
+        assert!(result.is_ok());
+        let (_, message) = result.unwrap();
+        assert_eq!(
+            message.elements[0],
+            MessageElement::Time(TimeExpression {
+                parameter: "t".to_string(),
+                style: DateTimeStyle::Short,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let message = parse_message_complete("{names, list} {names, list, or} {names, list, unit}").unwrap();
+        let styles: Vec<ListStyle> = message
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                MessageElement::List(list_expr) => Some(list_expr.style),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(styles, [ListStyle::And, ListStyle::Or, ListStyle::Unit]);
+
+        let error = parse_message_complete("{names, list, xor}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidArgumentStyle);
+    }
+
+    #[test]
+    fn test_parse_case_whitespace() {
+        let message = parse_message_complete("{g, select,\n  male {He}\n  female{ She }\n  other {They}\n}").unwrap();
+        let MessageElement::Select(select_expr) = &message.elements[0] else {
+            panic!("expected select");
+        };
+        let bodies: Vec<String> = select_expr.cases.iter().map(|case| case.message.to_string()).collect();
+        assert_eq!(bodies, ["He", " She ", "They"]);
+    }
+
+    fn nested_plural(depth: usize) -> String {
+        "{x, plural, other{".repeat(depth) + "#" + &"}}".repeat(depth)
+    }
+
+    #[test]
+    fn test_parse_nesting_too_deep() {
+        let template = nested_plural(1000);
+        let error = parse_message_complete(&template).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+        // Points at the first case body past the limit
+        let accepted = "{x, plural, other{".repeat(DEFAULT_MAX_DEPTH) + "{x, plural, other";
+        assert_eq!(error.offset, accepted.len());
+    }
+
+    #[test]
+    fn test_parse_nesting_within_limit() {
+        assert!(parse_message_complete(&nested_plural(DEFAULT_MAX_DEPTH)).is_ok());
+        assert!(parse_message_complete(&nested_plural(DEFAULT_MAX_DEPTH + 1)).is_err());
+
+        let error = parse_message_with_max_depth(&nested_plural(3), 2).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+        assert!(parse_message_with_max_depth(&nested_plural(3), 3).is_ok());
+
+        assert!(parse_message_borrowed(&nested_plural(DEFAULT_MAX_DEPTH)).is_ok());
+        let error = parse_message_borrowed(&nested_plural(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+    }
+
+    fn diagnose_input(input: &str) -> ParseError {
+        let (remaining, _) = parse_message(input).unwrap();
+        assert!(!remaining.is_empty());
+        diagnose(input, remaining)
+    }
+
+    #[test]
+    fn test_diagnose_unclosed_brace() {
+        let error = diagnose_input("Hello {name");
+        assert_eq!(error.kind, ParseErrorKind::UnclosedBrace);
+        assert_eq!(error.offset, 6);
+        assert_eq!(error.snippet, "{name");
+    }
+
+    #[test]
+    fn test_diagnose_unmatched_close_brace() {
+        let error = parse_message_complete("Hello name}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnmatchedCloseBrace);
+        assert_eq!(error.offset, 10);
+        assert_eq!(error.kind.to_string(), "unmatched '}'; quote it as \"'}'\" for a literal brace");
+        assert_ne!(error.kind, parse_message_complete("Hello {name").unwrap_err().kind);
+
+        let error = parse_message_complete("{g, select, other{x}}} done").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnmatchedCloseBrace);
+        assert_eq!(error.offset, 21);
+        assert_eq!(parse_message_borrowed("a}").unwrap_err().kind, ParseErrorKind::UnmatchedCloseBrace);
+
+        let message = parse_message_complete("Hello name'}'").unwrap();
+        assert_eq!(message.elements, [MessageElement::Text("Hello name}".to_string())]);
+    }
+
+    #[test]
+    fn test_diagnose_unclosed_case_body() {
+        let error = diagnose_input("{n, plural, one{# item");
+        assert_eq!(error.kind, ParseErrorKind::UnclosedBrace);
+        assert_eq!(error.offset, 15);
+    }
+
+    #[test]
+    fn test_diagnose_unknown_argument_type() {
+        let error = diagnose_input("Total: {amount, money}");
+        assert_eq!(error.kind, ParseErrorKind::UnknownArgumentType("money".to_string()));
+        assert_eq!(error.offset, 16);
+        assert_eq!(error.snippet, "money}");
+        assert_eq!(error.to_string(), "error at offset 16: unknown argument type 'money'");
+    }
+
+    #[test]
+    fn test_parse_message_complete_reports_unknown_type() {
+        let err = parse_message_complete("{d, duration, short}").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownArgumentType("duration".to_string()));
+        assert_eq!(err.offset, 4);
+    }
+
+    #[test]
+    fn test_diagnose_expected_selector() {
+        let error = diagnose_input("{count, plural, }");
+        assert_eq!(error.kind, ParseErrorKind::ExpectedSelector);
+        assert_eq!(error.offset, 16);
+    }
+
+    #[test]
+    fn test_diagnose_expected_case_body() {
+        let error = diagnose_input("{g, select, male He}");
+        assert_eq!(error.kind, ParseErrorKind::ExpectedCaseBody);
+        assert_eq!(error.offset, 17);
+
+        // A quoted selector is longer in the template than once unquoted
+        let error = parse_message_complete("{x, select, 'a, b'{c} other o}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ExpectedCaseBody);
+        assert_eq!(error.offset, 28);
+        assert_eq!(error.snippet, "o}");
+    }
+
+    #[test]
+    fn test_diagnose_invalid_style() {
+        let error = diagnose_input("{d, date, sometimes}");
+        assert_eq!(error.kind, ParseErrorKind::InvalidArgumentStyle);
+        assert_eq!(error.offset, 10);
+    }
+
+    #[test]
+    fn test_diagnose_nested_error() {
+        let error = diagnose_input("{g, select, other{Hi {n, bogus}}}");
+        assert_eq!(error.kind, ParseErrorKind::UnknownArgumentType("bogus".to_string()));
+        assert_eq!(error.offset, 25);
+    }
+
+    #[test]
+    fn test_parse_error_display_and_column() {
+        let input = "line one\nHello {name";
+        let error = diagnose_input(input);
+        assert_eq!(error.to_string(), "error at offset 15: unclosed '{'");
+        assert_eq!(error.column(input), 7);
+    }
+
+    #[test]
+    fn test_parse_message_complete_ok() {
+        let result = parse_message_complete("Hello {name}!");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().elements.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_message_complete_rejects_plural_without_cases() {
+        let result = parse_message_complete("{count, plural, }");
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind, ParseErrorKind::ExpectedSelector);
+    }
+
+    #[test]
+    fn test_parse_message_complete_rejects_trailing_input() {
+        let result = parse_message_complete("You have {count, plural, one{# item} other{# items}} {oops");
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnclosedBrace);
+        assert_eq!(err.offset, 53);
+    }
+
+    #[test]
+    fn test_parse_message_borrowed_matches_owned() {
+        let templates = [
+            "Hello {name}, you have {count, plural, =0{no items} one{# item} other{# items}}",
+            "{g, select, male{He} 'non-binary'{They} other{{who}}} paid {total, number, currency/EUR}",
+            "It''s '{literal}' # {n, plural, other{'#' is #}}",
+            "{d, date, short} {items, list, or}",
+        ];
+        for template in templates {
+            let borrowed = parse_message_borrowed(template).unwrap();
+            assert_eq!(borrowed.into_owned(), parse_message_complete(template).unwrap(), "{template}");
+        }
+    }
+
+    #[test]
+    fn test_parse_message_borrowed_slices_template() {
+        let message = parse_message_borrowed("Hi {name}, it''s {g, select, other{done}}").unwrap();
+        assert!(matches!(&message.elements[0], BorrowedElement::Text(Cow::Borrowed("Hi "))));
+        assert_eq!(message.elements[1], BorrowedElement::Parameter("name"));
+        assert!(matches!(&message.elements[2], BorrowedElement::Text(Cow::Owned(text)) if text == ", it's "));
+        let BorrowedElement::Select { parameter: "g", cases } = &message.elements[3] else {
+            panic!("expected select, got {:?}", message.elements[3]);
+        };
+        assert!(matches!(cases[0].selector, Cow::Borrowed("other")));
+    }
+
+    #[test]
+    fn test_parse_message_borrowed_errors() {
+        let error = parse_message_borrowed("Total: {amount, money}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnknownArgumentType("money".to_string()));
+        let error = parse_message_borrowed(&nested_plural(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+    }
+
+    #[test]
+    fn test_parse_message_keeps_remainder() {
+        let result = parse_message("Hello {name");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "{name");
+        assert_eq!(message.elements, vec![MessageElement::Text("Hello ".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_number_skeleton() {
+        let result = parse_message("{v, number, ::currency/USD .00}");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "");
+
+        if let MessageElement::Number(number_expr) = &message.elements[0] {
+            assert_eq!(number_expr.format_type, NumberFormatType::Skeleton("currency/USD .00".to_string()));
+        } else {
+            panic!("Expected number expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_number_currency_display() {
+        let result = parse_message("{price, number, currency/USD/code}");
+        assert!(result.is_ok());
+        let (remaining, message) = result.unwrap();
+        assert_eq!(remaining, "");
+
+        if let MessageElement::Number(number_expr) = &message.elements[0] {
+            assert_eq!(number_expr.format_type, NumberFormatType::Currency(Some("USD".to_string()), CurrencyDisplay::Code));
+        } else {
+            panic!("Expected number expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_number_compact() {
+        let (_, message) = parse_message("{views, number, compact}").unwrap();
+        if let MessageElement::Number(number_expr) = &message.elements[0] {
+            assert_eq!(number_expr.format_type, NumberFormatType::Compact(CompactStyle::Short));
+        } else {
+            panic!("Expected number expression");
+        }
+
+        let (_, message) = parse_message("{views, number, compact-long}").unwrap();
+        if let MessageElement::Number(number_expr) = &message.elements[0] {
+            assert_eq!(number_expr.format_type, NumberFormatType::Compact(CompactStyle::Long));
+        } else {
+            panic!("Expected number expression");
+        }
+    }
+
+    #[test]
+    fn test_parse_quoted_select_selector() {
+        let message = parse_message_complete("{x, select, 'a, b'{comma} 'it''s here'{quote} plain{p} other{o}}").unwrap();
+        let selectors: Vec<_> = match &message.elements[0] {
+            MessageElement::Select(select_expr) => select_expr.cases.iter().map(|case| case.selector.as_str()).collect(),
+            other => panic!("expected select, got {other:?}"),
+        };
+        assert_eq!(selectors, ["a, b", "it's here", "plain", "other"]);
+
+        assert!(parse_message_complete("{x, select, ''{empty} other{o}}").is_err());
+        assert!(parse_message_complete("{x, select, 'open{o} other{o}}").is_err());
+    }
+
+    #[test]
+    fn test_parse_lenient_argument_whitespace() {
+        let cases = [
+            ("{ count , plural , one {# item} other {# items} }", "{count, plural, one{# item} other{# items}}"),
+            ("{ count ,\n plural ,\n  =0 {none}\n  other {#}\n}", "{count, plural, =0{none} other{#}}"),
+            ("{ g , select , male {he} other {they} }", "{g, select, male{he} other{they}}"),
+            ("{ n , number }", "{n, number}"),
+            ("{ n , number , integer }", "{n, number, integer}"),
+            ("{\tn\t,\tnumber\t,\tcurrency/EUR\t}", "{n, number, currency/EUR}"),
+            ("{ n , number , :: .00 }", "{n, number, ::.00}"),
+            ("{ d , date , short } { t , time }", "{d, date, short} {t, time, medium}"),
+            ("{ l , list , or }", "{l, list, or}"),
+        ];
+        for (input, expected) in cases {
+            let message = parse_message_complete(input).unwrap_or_else(|err| panic!("{input:?}: {err}"));
+            assert_eq!(message.to_string(), expected);
+        }
+    }
+}
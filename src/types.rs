@@ -1,51 +1,259 @@
+use fixed_decimal::FixedDecimal;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageElement {
     Text(String),
     Parameter(String),
     Plural(PluralExpression),
     Select(SelectExpression),
     Number(NumberExpression),
+    Date(DateExpression),
+    Time(TimeExpression),
+    List(ListExpression),
+    // Unquoted `#` inside a plural sub-message, replaced by the plural count
+    PluralHash,
+    // Another message's elements, standing in for them where this element is. The
+    // parser never produces it; `MessageInterner` replaces identical cases with one
+    // shared message this way.
+    Shared(Arc<Message>),
+}
+
+// The variant of a `MessageElement`, without its contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementKind {
+    Text,
+    Parameter,
+    Plural,
+    Select,
+    Number,
+    Date,
+    Time,
+    List,
+    PluralHash,
+    Shared,
+}
+
+impl MessageElement {
+    pub fn kind(&self) -> ElementKind {
+        match self {
+            MessageElement::Text(_) => ElementKind::Text,
+            MessageElement::Parameter(_) => ElementKind::Parameter,
+            MessageElement::Plural(_) => ElementKind::Plural,
+            MessageElement::Select(_) => ElementKind::Select,
+            MessageElement::Number(_) => ElementKind::Number,
+            MessageElement::Date(_) => ElementKind::Date,
+            MessageElement::Time(_) => ElementKind::Time,
+            MessageElement::List(_) => ElementKind::List,
+            MessageElement::PluralHash => ElementKind::PluralHash,
+            MessageElement::Shared(_) => ElementKind::Shared,
+        }
+    }
+
+    // The parameter the element reads, for everything but text and `#`; a
+    // `currency/$code` parameter isn't included
+    pub fn argument_name(&self) -> Option<&str> {
+        match self {
+            MessageElement::Text(_) | MessageElement::PluralHash | MessageElement::Shared(_) => None,
+            MessageElement::Parameter(name) => Some(name),
+            MessageElement::Plural(plural_expr) => Some(&plural_expr.parameter),
+            MessageElement::Select(select_expr) => Some(&select_expr.parameter),
+            MessageElement::Number(number_expr) => Some(&number_expr.parameter),
+            MessageElement::Date(date_expr) => Some(&date_expr.parameter),
+            MessageElement::Time(time_expr) => Some(&time_expr.parameter),
+            MessageElement::List(list_expr) => Some(&list_expr.parameter),
+        }
+    }
+
+    pub fn is_plural(&self) -> bool {
+        matches!(self, MessageElement::Plural(_))
+    }
+
+    pub fn is_select(&self) -> bool {
+        matches!(self, MessageElement::Select(_))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PluralExpression {
     pub parameter: String,
     pub cases: Vec<PluralCase>,
+    // From `{n, plural, ::compact-short ...}`: the number skeleton that formats `#`
+    pub hash_skeleton: Option<String>,
+}
+
+// The selectors of the cases, in the order they're written; duplicates are kept so
+// coverage tooling sees the template as written
+impl PluralExpression {
+    pub fn selectors(&self) -> Vec<PluralSelector> {
+        self.cases.iter().map(|case| case.selector).collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PluralCase {
     pub selector: PluralSelector,
     pub message: Message,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectExpression {
     pub parameter: String,
     pub cases: Vec<SelectCase>,
 }
 
+// Like `PluralExpression::selectors`
+impl SelectExpression {
+    pub fn selectors(&self) -> Vec<&str> {
+        self.cases.iter().map(|case| case.selector.as_str()).collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectCase {
     pub selector: String,
     pub message: Message,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NumberExpression {
     pub parameter: String,
     pub format_type: NumberFormatType,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NumberFormatType {
     Number,        // Basic number formatting
     Integer,       // Integer formatting (no decimals)
     Percent,       // Percentage formatting
-    Currency(String), // Currency formatting with optional currency code
+    // Currency formatting; no code means the locale's currency, a `$name` code is read from parameter `name`
+    Currency(Option<String>, CurrencyDisplay),
+    Compact(CompactStyle), // Compact notation, e.g. `1.2K`
+    Skeleton(String), // ICU number skeleton following `::`
+    Spellout,      // Number in words, e.g. `forty-two`
+    Ordinal,       // Number with an ordinal suffix, e.g. `3rd`
+    Scientific,    // One integer digit and an exponent, e.g. `4.2E4`
+    Engineering,   // Exponent a multiple of three, e.g. `42E3`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompactStyle {
+    Short, // `1.2K`
+    Long,  // `1.2 thousand`
+}
+
+impl fmt::Display for NumberFormatType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberFormatType::Number => Ok(()),
+            NumberFormatType::Integer => write!(f, "integer"),
+            NumberFormatType::Percent => write!(f, "percent"),
+            NumberFormatType::Currency(code, display) => {
+                write!(f, "currency")?;
+                if let Some(code) = code {
+                    write!(f, "/{code}")?;
+                }
+                match display {
+                    CurrencyDisplay::Symbol => Ok(()),
+                    CurrencyDisplay::NarrowSymbol => write!(f, "/narrow"),
+                    CurrencyDisplay::Code => write!(f, "/code"),
+                }
+            }
+            NumberFormatType::Compact(CompactStyle::Short) => write!(f, "compact"),
+            NumberFormatType::Compact(CompactStyle::Long) => write!(f, "compact-long"),
+            NumberFormatType::Skeleton(skeleton) => write!(f, "::{skeleton}"),
+            NumberFormatType::Spellout => write!(f, "spellout"),
+            NumberFormatType::Ordinal => write!(f, "ordinal"),
+            NumberFormatType::Scientific => write!(f, "scientific"),
+            NumberFormatType::Engineering => write!(f, "engineering"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CurrencyDisplay {
+    Symbol,       // Standard symbol, e.g. `$` or `US$`
+    NarrowSymbol, // Narrow symbol, e.g. `$`
+    Code,         // ISO 4217 code, e.g. `USD`
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DateExpression {
+    pub parameter: String,
+    pub style: DateTimeStyle,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeExpression {
+    pub parameter: String,
+    pub style: DateTimeStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DateTimeStyle {
+    Short,
+    Medium,
+    Long,
+    Full,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListExpression {
+    pub parameter: String,
+    pub style: ListStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ListStyle {
+    And,  // `A, B, and C`
+    Or,   // `A, B, or C`
+    Unit, // `A, B, C`
+}
+
+impl fmt::Display for ListStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListStyle::And => write!(f, "and"),
+            ListStyle::Or => write!(f, "or"),
+            ListStyle::Unit => write!(f, "unit"),
+        }
+    }
+}
+
+impl fmt::Display for DateTimeStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateTimeStyle::Short => write!(f, "short"),
+            DateTimeStyle::Medium => write!(f, "medium"),
+            DateTimeStyle::Long => write!(f, "long"),
+            DateTimeStyle::Full => write!(f, "full"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PluralSelector {
     Zero,
     One,
@@ -54,6 +262,9 @@ pub enum PluralSelector {
     Many,
     Other,
     Exact(i64),
+    // Matches counts from the first bound to the second, inclusive; written `2..4`.
+    // A crate extension, not part of ICU MessageFormat.
+    Range(i64, i64),
 }
 
 impl PluralSelector {
@@ -76,21 +287,444 @@ impl PluralSelector {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl fmt::Display for PluralSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluralSelector::Zero => write!(f, "zero"),
+            PluralSelector::One => write!(f, "one"),
+            PluralSelector::Two => write!(f, "two"),
+            PluralSelector::Few => write!(f, "few"),
+            PluralSelector::Many => write!(f, "many"),
+            PluralSelector::Other => write!(f, "other"),
+            PluralSelector::Exact(n) => write!(f, "={n}"),
+            PluralSelector::Range(start, end) => write!(f, "{start}..{end}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     pub elements: Vec<MessageElement>,
 }
 
+// Shared elements compare as the elements they stand for, so interning a message
+// doesn't change what it's equal to
+impl PartialEq for Message {
+    fn eq(&self, other: &Self) -> bool {
+        let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+        self.collect_unshared_elements(&mut ours);
+        other.collect_unshared_elements(&mut theirs);
+        ours == theirs
+    }
+}
+
 impl Message {
     pub fn new(elements: Vec<MessageElement>) -> Self {
         Self { elements }
     }
+
+    fn collect_unshared_elements<'a>(&'a self, elements: &mut Vec<&'a MessageElement>) {
+        for element in &self.elements {
+            match element {
+                MessageElement::Shared(message) => message.collect_unshared_elements(elements),
+                element => elements.push(element),
+            }
+        }
+    }
+
+    // The message an interned case stands for, or the message itself
+    pub(crate) fn unshared(&self) -> &Message {
+        match self.elements.as_slice() {
+            [MessageElement::Shared(message)] => message.unshared(),
+            _ => self,
+        }
+    }
+
+    // Serializes the message back to ICU MessageFormat source
+    pub fn to_icu_string(&self) -> String {
+        self.to_string()
+    }
+
+    // Every parameter referenced anywhere in the message, including inside plural and select cases
+    pub fn parameter_names(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        self.collect_parameter_names(&mut names);
+        names
+    }
+
+    fn collect_parameter_names(&self, names: &mut BTreeSet<String>) {
+        for element in &self.elements {
+            match element {
+                MessageElement::Text(_) | MessageElement::PluralHash => {}
+                MessageElement::Shared(message) => message.collect_parameter_names(names),
+                MessageElement::Parameter(name) => {
+                    names.insert(name.clone());
+                }
+                MessageElement::Plural(plural_expr) => {
+                    names.insert(plural_expr.parameter.clone());
+                    for case in &plural_expr.cases {
+                        case.message.collect_parameter_names(names);
+                    }
+                }
+                MessageElement::Select(select_expr) => {
+                    names.insert(select_expr.parameter.clone());
+                    for case in &select_expr.cases {
+                        case.message.collect_parameter_names(names);
+                    }
+                }
+                MessageElement::Number(number_expr) => {
+                    names.insert(number_expr.parameter.clone());
+                    if let NumberFormatType::Currency(Some(code), _) = &number_expr.format_type
+                        && let Some(name) = code.strip_prefix('$')
+                    {
+                        names.insert(name.to_string());
+                    }
+                }
+                MessageElement::Date(date_expr) => {
+                    names.insert(date_expr.parameter.clone());
+                }
+                MessageElement::Time(time_expr) => {
+                    names.insert(time_expr.parameter.clone());
+                }
+                MessageElement::List(list_expr) => {
+                    names.insert(list_expr.parameter.clone());
+                }
+            }
+        }
+    }
+
+    // The literal text with every placeholder dropped, for spell-checking and length
+    // estimates. Every case of a plural or select is included, one per line.
+    pub fn literal_text(&self) -> String {
+        let mut text = String::new();
+        self.collect_literal_text(&mut text);
+        text
+    }
+
+    fn collect_literal_text(&self, text: &mut String) {
+        for element in &self.elements {
+            let cases: Vec<&Message> = match element {
+                MessageElement::Text(literal) => {
+                    text.push_str(literal);
+                    continue;
+                }
+                MessageElement::Shared(message) => {
+                    message.collect_literal_text(text);
+                    continue;
+                }
+                MessageElement::Plural(plural_expr) => plural_expr.cases.iter().map(|case| &case.message).collect(),
+                MessageElement::Select(select_expr) => select_expr.cases.iter().map(|case| &case.message).collect(),
+                _ => continue,
+            };
+            for (i, case) in cases.into_iter().enumerate() {
+                if i > 0 {
+                    text.push('\n');
+                }
+                case.collect_literal_text(text);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for element in &self.elements {
+            write!(f, "{element}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MessageElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageElement::Text(text) => write_escaped_text(f, text),
+            MessageElement::Parameter(name) => write!(f, "{{{name}}}"),
+            MessageElement::Plural(plural_expr) => {
+                write!(f, "{{{}, plural,", plural_expr.parameter)?;
+                if let Some(skeleton) = &plural_expr.hash_skeleton {
+                    write!(f, " ::{skeleton}")?;
+                }
+                for case in &plural_expr.cases {
+                    write!(f, " {}{{{}}}", case.selector, case.message)?;
+                }
+                write!(f, "}}")
+            }
+            MessageElement::Select(select_expr) => {
+                write!(f, "{{{}, select,", select_expr.parameter)?;
+                for case in &select_expr.cases {
+                    write!(f, " ")?;
+                    write_selector(f, &case.selector)?;
+                    write!(f, "{{{}}}", case.message)?;
+                }
+                write!(f, "}}")
+            }
+            MessageElement::Number(number_expr) => match &number_expr.format_type {
+                NumberFormatType::Number => write!(f, "{{{}, number}}", number_expr.parameter),
+                format_type => write!(f, "{{{}, number, {format_type}}}", number_expr.parameter),
+            },
+            MessageElement::Date(date_expr) => write!(f, "{{{}, date, {}}}", date_expr.parameter, date_expr.style),
+            MessageElement::Time(time_expr) => write!(f, "{{{}, time, {}}}", time_expr.parameter, time_expr.style),
+            MessageElement::List(list_expr) => write!(f, "{{{}, list, {}}}", list_expr.parameter, list_expr.style),
+            MessageElement::PluralHash => write!(f, "#"),
+            MessageElement::Shared(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+// Selectors that aren't plain identifiers (words joined by `.` or `-`) are quoted
+fn write_selector(f: &mut fmt::Formatter<'_>, selector: &str) -> fmt::Result {
+    let is_word = |word: &str| !word.is_empty() && word.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if selector.split(['.', '-']).all(is_word) {
+        return write!(f, "{selector}");
+    }
+    write!(f, "'{}'", selector.replace('\'', "''"))
+}
+
+// Quotes syntax characters and doubles apostrophes so the text re-parses as literal.
+// A run of syntax characters shares one quoted section: quoting them one by one
+// would write `'{''}'`, where the `''` in the middle reads as a quoted apostrophe.
+fn write_escaped_text(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    let is_syntax = |c: char| matches!(c, '{' | '}' | '#');
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => write!(f, "''")?,
+            c if is_syntax(c) => {
+                write!(f, "'{c}")?;
+                while let Some(next) = chars.next_if(|&next| is_syntax(next) || next == '\'') {
+                    match next {
+                        '\'' => write!(f, "''")?,
+                        _ => write!(f, "{next}")?,
+                    }
+                }
+                write!(f, "'")?;
+            }
+            _ => write!(f, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+// A message that borrows its text and names from the template it was parsed from,
+// so parsing allocates only for the element lists and for text containing
+// apostrophe escapes. Number, date, time and list arguments are kept as their
+// owned elements; `into_owned` converts the whole tree for formatting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowedMessage<'a> {
+    pub elements: Vec<BorrowedElement<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedElement<'a> {
+    Text(Cow<'a, str>),
+    Parameter(&'a str),
+    Plural { parameter: &'a str, hash_skeleton: Option<&'a str>, cases: Vec<BorrowedPluralCase<'a>> },
+    Select { parameter: &'a str, cases: Vec<BorrowedSelectCase<'a>> },
+    Argument(MessageElement),
+    PluralHash,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowedPluralCase<'a> {
+    pub selector: PluralSelector,
+    pub message: BorrowedMessage<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowedSelectCase<'a> {
+    pub selector: Cow<'a, str>,
+    pub message: BorrowedMessage<'a>,
+}
+
+impl BorrowedMessage<'_> {
+    pub fn into_owned(self) -> Message {
+        Message::new(self.elements.into_iter().map(BorrowedElement::into_owned).collect())
+    }
+}
+
+impl BorrowedElement<'_> {
+    pub fn into_owned(self) -> MessageElement {
+        match self {
+            BorrowedElement::Text(text) => MessageElement::Text(text.into_owned()),
+            BorrowedElement::Parameter(name) => MessageElement::Parameter(name.to_string()),
+            BorrowedElement::Plural { parameter, hash_skeleton, cases } => MessageElement::Plural(PluralExpression {
+                parameter: parameter.to_string(),
+                hash_skeleton: hash_skeleton.map(str::to_string),
+                cases: cases
+                    .into_iter()
+                    .map(|case| PluralCase { selector: case.selector, message: case.message.into_owned() })
+                    .collect(),
+            }),
+            BorrowedElement::Select { parameter, cases } => MessageElement::Select(SelectExpression {
+                parameter: parameter.to_string(),
+                cases: cases
+                    .into_iter()
+                    .map(|case| SelectCase { selector: case.selector.into_owned(), message: case.message.into_owned() })
+                    .collect(),
+            }),
+            BorrowedElement::Argument(element) => element,
+            BorrowedElement::PluralHash => MessageElement::PluralHash,
+        }
+    }
+}
+
+impl From<BorrowedMessage<'_>> for Message {
+    fn from(message: BorrowedMessage<'_>) -> Self {
+        message.into_owned()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ParameterValue<'a> {
     String(&'a str),
     Number(i64),
+    // Integers outside the `i64` range, kept exact instead of truncated
+    BigInteger(i128),
+    Float(f64),
+    Bool(bool),
+    // An exact decimal, formatted without a round trip through `f64`
+    Decimal(&'a FixedDecimal),
+    List(ListItems<'a>),
+    // An explicitly absent value: interpolates as nothing and selects the `null`
+    // case, falling back to `other`
+    Null,
+}
+
+impl<'a> ParameterValue<'a> {
+    // The select value: strings as-is and booleans as `true` or `false`
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            ParameterValue::String(s) => Some(s),
+            ParameterValue::Bool(b) => Some(if b { "true" } else { "false" }),
+            _ => None,
+        }
+    }
+
+    // The number the formatter reads for number arguments, plural counts and `#`: any
+    // finite number, and numeric strings, which may have an exponent as in `1e3`
+    pub fn as_decimal(&self) -> Option<FixedDecimal> {
+        match *self {
+            ParameterValue::Number(n) => Some(FixedDecimal::from(n)),
+            ParameterValue::BigInteger(n) => Some(FixedDecimal::from(n)),
+            ParameterValue::Float(f) => f64_to_decimal(f),
+            ParameterValue::Decimal(d) => Some(d.clone()),
+            ParameterValue::String(s) => parse_decimal(s),
+            ParameterValue::Bool(_) | ParameterValue::List(_) | ParameterValue::Null => None,
+        }
+    }
+
+    // `as_decimal`, when it is whole and fits in an `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            ParameterValue::Number(n) => Some(n),
+            _ => decimal_to_i64(&self.as_decimal()?),
+        }
+    }
+
+    // `as_decimal` rounded to the nearest `f64`, for integers beyond 2^53 and long decimals
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            ParameterValue::Float(f) => f.is_finite().then_some(f),
+            _ => self.as_decimal()?.to_string().parse().ok(),
+        }
+    }
+
+    // The kind of value, as named in type mismatch errors
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ParameterValue::String(_) => "string",
+            ParameterValue::Number(_)
+            | ParameterValue::BigInteger(_)
+            | ParameterValue::Float(_)
+            | ParameterValue::Decimal(_) => "number",
+            ParameterValue::Bool(_) => "boolean",
+            ParameterValue::List(_) => "list",
+            ParameterValue::Null => "null",
+        }
+    }
+}
+
+// `f64::fract` needs `std`, so wholeness is checked by round-tripping through `i64`
+pub(crate) fn whole_i64(value: f64) -> Option<i64> {
+    let in_range = value >= i64::MIN as f64 && value <= i64::MAX as f64;
+    (in_range && value as i64 as f64 == value).then_some(value as i64)
+}
+
+// `None` for infinities and NaN
+fn f64_to_decimal(value: f64) -> Option<FixedDecimal> {
+    match whole_i64(value) {
+        Some(whole) => Some(FixedDecimal::from(whole)),
+        None => value.to_string().parse().ok(),
+    }
+}
+
+// Strings whose digits would reach past this magnitude are rejected, so that
+// `1e32000` can't expand into 32,000 digits; `f64` stops at about 10^308
+const MAX_STRING_MAGNITUDE: i32 = 1000;
+
+// Parses a numeric string parameter straight into a `FixedDecimal`, so it keeps digits
+// an `f64` would round away, like those of `9007199254740993`. An exponent is applied
+// exactly: `1e3` is `1000` and `1.5e-2` is `0.015`. Forms the decimal parser rejects,
+// like `.5`, go through `f64` instead.
+fn parse_decimal(s: &str) -> Option<FixedDecimal> {
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(index) => (&s[..index], s[index + 1..].parse::<i32>().ok()),
+        None => (s, Some(0)),
+    };
+    let (Ok(mut decimal), Some(exponent)) = (mantissa.parse::<FixedDecimal>(), exponent) else {
+        return f64_to_decimal(s.parse().ok()?);
+    };
+    let magnitudes = decimal.magnitude_range();
+    let in_range = |magnitude: i16| (-MAX_STRING_MAGNITUDE..=MAX_STRING_MAGNITUDE).contains(&(i32::from(magnitude) + exponent));
+    if !in_range(*magnitudes.start()) || !in_range(*magnitudes.end()) {
+        return None;
+    }
+    decimal.multiply_pow10(i16::try_from(exponent).ok()?);
+    Some(decimal)
+}
+
+// Trailing fraction zeros don't count, so `3.00` is 3
+pub(crate) fn decimal_to_i64(decimal: &FixedDecimal) -> Option<i64> {
+    if decimal.nonzero_magnitude_end() < 0 {
+        return None;
+    }
+    let mut whole = decimal.clone();
+    whole.trim_end();
+    whole.to_string().parse().ok()
+}
+
+// The items of a list parameter, borrowed from either string slices or owned strings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListItems<'a> {
+    Borrowed(&'a [&'a str]),
+    Owned(&'a [String]),
+}
+
+impl<'a> ListItems<'a> {
+    pub fn len(&self) -> usize {
+        match self {
+            ListItems::Borrowed(items) => items.len(),
+            ListItems::Owned(items) => items.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        match *self {
+            ListItems::Borrowed(items) => items.get(index).copied(),
+            ListItems::Owned(items) => items.get(index).map(String::as_str),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + Clone + use<'a> {
+        let items = *self;
+        (0..items.len()).filter_map(move |index| items.get(index))
+    }
 }
 
 // Trait for types that can be used as parameter values without taking ownership
@@ -110,9 +744,44 @@ impl AsParameterValue for i64 {
     }
 }
 
-impl AsParameterValue for i32 {
+// Integer types that always fit in an `i64`
+macro_rules! impl_as_parameter_value_small_int {
+    ($($t:ty),*) => {
+        $(impl AsParameterValue for $t {
+            fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+                ParameterValue::Number(i64::from(*self))
+            }
+        })*
+    };
+}
+
+impl_as_parameter_value_small_int!(i8, i16, i32, u8, u16, u32);
+
+// Integer types that may exceed `i64`, falling back to `BigInteger`
+macro_rules! impl_as_parameter_value_wide_int {
+    ($($t:ty),*) => {
+        $(impl AsParameterValue for $t {
+            fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+                match i64::try_from(*self) {
+                    Ok(n) => ParameterValue::Number(n),
+                    Err(_) => ParameterValue::BigInteger(*self as i128),
+                }
+            }
+        })*
+    };
+}
+
+impl_as_parameter_value_wide_int!(u64, usize, i128);
+
+impl AsParameterValue for f64 {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::Float(*self)
+    }
+}
+
+impl AsParameterValue for bool {
     fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
-        ParameterValue::Number(*self as i64)
+        ParameterValue::Bool(*self)
     }
 }
 
@@ -122,6 +791,205 @@ impl AsParameterValue for String {
     }
 }
 
+impl AsParameterValue for FixedDecimal {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::Decimal(self)
+    }
+}
+
+impl AsParameterValue for &[&str] {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::List(ListItems::Borrowed(self))
+    }
+}
+
+impl<const N: usize> AsParameterValue for [&str; N] {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::List(ListItems::Borrowed(self))
+    }
+}
+
+impl AsParameterValue for Vec<&str> {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::List(ListItems::Borrowed(self))
+    }
+}
+
+impl AsParameterValue for Vec<String> {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        ParameterValue::List(ListItems::Owned(self))
+    }
+}
+
+// `None` is passed as `Null` rather than left out, so optional fields don't need
+// their own branch at the call site
+impl<T: AsParameterValue> AsParameterValue for Option<T> {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        match self {
+            Some(value) => value.as_parameter_value(),
+            None => ParameterValue::Null,
+        }
+    }
+}
+
+// Conversions for building values by hand, e.g. for a `HashMap<String, ParameterValue>`;
+// integers follow the same `i64`/`BigInteger` split as `params!`
+impl<'a> From<&'a str> for ParameterValue<'a> {
+    fn from(value: &'a str) -> Self {
+        ParameterValue::String(value)
+    }
+}
+
+impl<'a> From<&'a String> for ParameterValue<'a> {
+    fn from(value: &'a String) -> Self {
+        ParameterValue::String(value)
+    }
+}
+
+macro_rules! impl_from_small_int_for_parameter_value {
+    ($($t:ty),*) => {
+        $(impl From<$t> for ParameterValue<'_> {
+            fn from(value: $t) -> Self {
+                ParameterValue::Number(i64::from(value))
+            }
+        })*
+    };
+}
+
+impl_from_small_int_for_parameter_value!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_from_wide_int_for_parameter_value {
+    ($($t:ty),*) => {
+        $(impl From<$t> for ParameterValue<'_> {
+            fn from(value: $t) -> Self {
+                match i64::try_from(value) {
+                    Ok(n) => ParameterValue::Number(n),
+                    Err(_) => ParameterValue::BigInteger(value as i128),
+                }
+            }
+        })*
+    };
+}
+
+impl_from_wide_int_for_parameter_value!(u64, usize, i128);
+
+impl From<f64> for ParameterValue<'_> {
+    fn from(value: f64) -> Self {
+        ParameterValue::Float(value)
+    }
+}
+
+impl From<bool> for ParameterValue<'_> {
+    fn from(value: bool) -> Self {
+        ParameterValue::Bool(value)
+    }
+}
+
+impl<'a> From<&'a FixedDecimal> for ParameterValue<'a> {
+    fn from(value: &'a FixedDecimal) -> Self {
+        ParameterValue::Decimal(value)
+    }
+}
+
+impl<'a> From<&'a [&'a str]> for ParameterValue<'a> {
+    fn from(items: &'a [&'a str]) -> Self {
+        ParameterValue::List(ListItems::Borrowed(items))
+    }
+}
+
+impl<'a, T: Into<ParameterValue<'a>>> From<Option<T>> for ParameterValue<'a> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(ParameterValue::Null, Into::into)
+    }
+}
+
+// An owned counterpart of `ParameterValue`, for parameters assembled at runtime
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedParameterValue {
+    String(String),
+    Number(i64),
+    BigInteger(i128),
+    Float(f64),
+    Bool(bool),
+    Decimal(FixedDecimal),
+    List(Vec<String>),
+    Null,
+}
+
+impl AsParameterValue for OwnedParameterValue {
+    fn as_parameter_value<'a>(&'a self) -> ParameterValue<'a> {
+        match self {
+            OwnedParameterValue::String(value) => ParameterValue::String(value),
+            OwnedParameterValue::Number(value) => ParameterValue::Number(*value),
+            OwnedParameterValue::BigInteger(value) => ParameterValue::BigInteger(*value),
+            OwnedParameterValue::Float(value) => ParameterValue::Float(*value),
+            OwnedParameterValue::Bool(value) => ParameterValue::Bool(*value),
+            OwnedParameterValue::Decimal(value) => ParameterValue::Decimal(value),
+            OwnedParameterValue::List(items) => ParameterValue::List(ListItems::Owned(items)),
+            OwnedParameterValue::Null => ParameterValue::Null,
+        }
+    }
+}
+
+impl From<ParameterValue<'_>> for OwnedParameterValue {
+    fn from(value: ParameterValue<'_>) -> Self {
+        match value {
+            ParameterValue::String(value) => OwnedParameterValue::String(value.to_string()),
+            ParameterValue::Number(value) => OwnedParameterValue::Number(value),
+            ParameterValue::BigInteger(value) => OwnedParameterValue::BigInteger(value),
+            ParameterValue::Float(value) => OwnedParameterValue::Float(value),
+            ParameterValue::Bool(value) => OwnedParameterValue::Bool(value),
+            ParameterValue::Decimal(value) => OwnedParameterValue::Decimal(value.clone()),
+            ParameterValue::List(items) => OwnedParameterValue::List(items.iter().map(str::to_string).collect()),
+            ParameterValue::Null => OwnedParameterValue::Null,
+        }
+    }
+}
+
+// Every type usable with `params!` converts into an owned value the same way
+macro_rules! impl_from_for_owned_parameter_value {
+    ($($t:ty),*) => {
+        $(impl From<$t> for OwnedParameterValue {
+            fn from(value: $t) -> Self {
+                value.as_parameter_value().into()
+            }
+        })*
+    };
+}
+
+impl_from_for_owned_parameter_value!(&str, i8, i16, i32, i64, u8, u16, u32, u64, usize, i128, f64, bool, Vec<&str>);
+
+impl From<String> for OwnedParameterValue {
+    fn from(value: String) -> Self {
+        OwnedParameterValue::String(value)
+    }
+}
+
+impl From<FixedDecimal> for OwnedParameterValue {
+    fn from(value: FixedDecimal) -> Self {
+        OwnedParameterValue::Decimal(value)
+    }
+}
+
+impl From<Vec<String>> for OwnedParameterValue {
+    fn from(items: Vec<String>) -> Self {
+        OwnedParameterValue::List(items)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateKeyError {
+    pub key: String,
+}
+
+impl fmt::Display for DuplicateKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Duplicate parameter key: {}", self.key)
+    }
+}
+
+impl core::error::Error for DuplicateKeyError {}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Parameters<'a> {
     pairs: &'a [(&'a str, ParameterValue<'a>)],
@@ -132,16 +1000,21 @@ impl<'a> Parameters<'a> {
         Self { pairs: &[] }
     }
 
+    // Panics on duplicate keys; use `try_from_slice` for runtime data
     pub fn from_slice(pairs: &'a [(&'a str, ParameterValue<'a>)]) -> Self {
+        Self::try_from_slice(pairs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_slice(pairs: &'a [(&'a str, ParameterValue<'a>)]) -> Result<Self, DuplicateKeyError> {
         // Validate that all keys are distinct
         for (i, (key, _)) in pairs.iter().enumerate() {
             for (other_key, _) in pairs.iter().skip(i + 1) {
                 if key == other_key {
-                    panic!("Duplicate parameter key: {key}");
+                    return Err(DuplicateKeyError { key: key.to_string() });
                 }
             }
         }
-        Self { pairs }
+        Ok(Self { pairs })
     }
 
     pub fn get(&self, key: &str) -> Option<&ParameterValue<'a>> {
@@ -149,6 +1022,106 @@ impl<'a> Parameters<'a> {
     }
 }
 
+// Builds `OwnedParameters` incrementally; inserting an existing key replaces its value
+#[derive(Debug, Clone, Default)]
+pub struct ParametersBuilder {
+    pairs: Vec<(String, OwnedParameterValue)>,
+}
+
+impl ParametersBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(mut self, key: impl Into<String>, value: impl Into<OwnedParameterValue>) -> Self {
+        let key = key.into();
+        let value = value.into();
+        match self.pairs.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.pairs.push((key, value)),
+        }
+        self
+    }
+
+    pub fn build(self) -> OwnedParameters {
+        OwnedParameters { pairs: self.pairs }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnedParameters {
+    pairs: Vec<(String, OwnedParameterValue)>,
+}
+
+impl OwnedParameters {
+    pub fn builder() -> ParametersBuilder {
+        ParametersBuilder::new()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&OwnedParameterValue> {
+        self.pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+// A lookup of parameter values by name, so messages can be formatted from
+// borrowed slices (`Parameters`) as well as runtime maps
+pub trait ParameterSource {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>>;
+
+    // Layers `overrides` on top of this source; keys present in both take the value
+    // from `overrides`
+    fn merge<O: ParameterSource>(self, overrides: O) -> Merged<Self, O>
+    where
+        Self: Sized,
+    {
+        Merged { defaults: self, overrides }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Merged<D, O> {
+    defaults: D,
+    overrides: O,
+}
+
+impl<D: ParameterSource, O: ParameterSource> ParameterSource for Merged<D, O> {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        self.overrides.get(key).or_else(|| self.defaults.get(key))
+    }
+}
+
+impl ParameterSource for Parameters<'_> {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        Parameters::get(self, key).copied()
+    }
+}
+
+impl<T: ParameterSource + ?Sized> ParameterSource for &T {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        (**self).get(key)
+    }
+}
+
+impl ParameterSource for OwnedParameters {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        OwnedParameters::get(self, key).map(AsParameterValue::as_parameter_value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParameterSource for HashMap<String, ParameterValue<'_>> {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        HashMap::get(self, key).copied()
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParameterSource for HashMap<String, String> {
+    fn get(&self, key: &str) -> Option<ParameterValue<'_>> {
+        HashMap::get(self, key).map(|value| ParameterValue::String(value.as_str()))
+    }
+}
+
 // Convenience macro for creating parameters
 #[macro_export]
 macro_rules! params {
@@ -176,6 +1149,52 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_try_from_slice_duplicate_keys() {
+        let pairs = [
+            ("name", ParameterValue::String("Alice")),
+            ("age", ParameterValue::Number(25)),
+            ("age", ParameterValue::Number(26)),
+        ];
+        let error = Parameters::try_from_slice(&pairs).unwrap_err();
+        assert_eq!(error, DuplicateKeyError { key: "age".to_string() });
+        assert_eq!(error.to_string(), "Duplicate parameter key: age");
+
+        let params = Parameters::try_from_slice(&pairs[..2]).unwrap();
+        assert_eq!(params.get("age"), Some(&ParameterValue::Number(25)));
+    }
+
+    #[test]
+    fn test_parameter_value_accessors() {
+        let decimal: FixedDecimal = "12.50".parse().unwrap();
+        let whole: FixedDecimal = "-40".parse().unwrap();
+
+        assert_eq!(ParameterValue::String("male").as_str(), Some("male"));
+        assert_eq!(ParameterValue::Bool(false).as_str(), Some("false"));
+        assert_eq!(ParameterValue::Number(3).as_str(), None);
+
+        assert_eq!(ParameterValue::Number(3).as_i64(), Some(3));
+        assert_eq!(ParameterValue::Float(2.0).as_i64(), Some(2));
+        assert_eq!(ParameterValue::Float(2.5).as_i64(), None);
+        assert_eq!(ParameterValue::Decimal(&whole).as_i64(), Some(-40));
+        assert_eq!(ParameterValue::Decimal(&"3.00".parse().unwrap()).as_i64(), Some(3));
+        assert_eq!(ParameterValue::Decimal(&decimal).as_i64(), None);
+        assert_eq!(ParameterValue::String("17").as_i64(), Some(17));
+        assert_eq!(ParameterValue::String("1.5").as_i64(), None);
+        assert_eq!(ParameterValue::BigInteger(i128::MAX).as_i64(), None);
+
+        assert_eq!(ParameterValue::Number(3).as_f64(), Some(3.0));
+        assert_eq!(ParameterValue::Decimal(&decimal).as_f64(), Some(12.5));
+        assert_eq!(ParameterValue::String("1.5").as_f64(), Some(1.5));
+        assert_eq!(ParameterValue::String("abc").as_f64(), None);
+        assert_eq!(ParameterValue::String("NaN").as_f64(), None);
+        assert_eq!(ParameterValue::Float(f64::INFINITY).as_f64(), None);
+        assert_eq!(ParameterValue::String("1.5e-2").as_decimal(), Some("0.015".parse().unwrap()));
+        assert_eq!(ParameterValue::Bool(true).as_f64(), None);
+        assert_eq!(ParameterValue::Null.as_str(), None);
+        assert_eq!(ParameterValue::Null.as_f64(), None);
+    }
+
     #[test]
     fn test_unique_keys_ok() {
         let params = Parameters::from_slice(&[
@@ -208,4 +1227,336 @@ mod tests {
             "city" => city
         ));
     }
+
+    #[test]
+    fn test_params_macro_with_float() {
+        let test_fn = |params: Parameters| {
+            assert_eq!(params.get("price"), Some(&ParameterValue::Float(19.99)));
+        };
+
+        test_fn(params!("price" => 19.99));
+    }
+
+    #[test]
+    fn test_display_escapes_literals() {
+        let message = Message::new(vec![
+            MessageElement::Text("It's {literal} #1 ".to_string()),
+            MessageElement::Parameter("name".to_string()),
+        ]);
+        assert_eq!(message.to_icu_string(), "It''s '{'literal'}' '#'1 {name}");
+
+        // Adjacent syntax characters share one quoted run
+        let message = Message::new(vec![MessageElement::Text("}#' {}'".to_string())]);
+        assert_eq!(message.to_icu_string(), "'}#''' '{}'''");
+        assert_eq!(crate::parser::parse_message_complete(&message.to_icu_string()).unwrap(), message);
+    }
+
+    #[test]
+    fn test_display_round_trips_through_parser() {
+        let templates = [
+            "Hello {name}!",
+            "You have {count, plural, =0{no items} one{# item} other{# items}} in your cart.",
+            "{n, plural, =1{one} 2..4{a few} -3..-1{below} other{#}}",
+            "{count, plural, =0{} other{# items}}{g, select, male{} other{}}",
+            "{gender, select, male{He} female{She} other{They}} liked {count, plural, one{your post} other{# posts}}",
+            "{outer, plural, other{# - {inner, plural, other{#}}}}",
+            "Total: {price, number, currency/EUR} ({ratio, number, percent}, {n, number, integer}, {v, number})",
+            "{price, number, currency/USD/code} {views, number, compact} {views, number, compact-long}",
+            "{price, number, currency/$account.currency/narrow}",
+            "{v, number, scientific} {v, number, engineering} {v, number, ::scientific .00}",
+            "{x, select, 'a, b'{comma} 'it''s'{quote} en-US{dash} other{o}}",
+            "{price, number, currency} {price, number, currency/narrow} {price, number, currency/code}",
+            "{x, number, ::.00 group-off}",
+            "{n, number, spellout} {n, number, ordinal}",
+            "Due {d, date, long} at {t, time, short}",
+            "Invited {names, list} or {others, list, or}",
+            "It''s a '{'literal'}' and I''m '#'1",
+            "{n, plural, other{Issue '#'{id} has # comments}}",
+            "the students'",
+        ];
+
+        for template in templates {
+            let (remaining, message) = crate::parser::parse_message(template).unwrap();
+            assert_eq!(remaining, "", "template did not fully parse: {template}");
+
+            let serialized = message.to_icu_string();
+            let (remaining, reparsed) = crate::parser::parse_message(&serialized).unwrap();
+            assert_eq!(remaining, "", "serialized form did not fully parse: {serialized}");
+            assert_eq!(reparsed, message, "round trip changed the message: {template} -> {serialized}");
+        }
+    }
+
+    // A small xorshift generator, so the random round trip below is reproducible
+    // from its seed without a property-testing dependency
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.below(items.len())]
+        }
+    }
+
+    // Messages as the parser produces them: no empty or adjacent text, and `#` only
+    // inside case bodies
+    fn random_message(rng: &mut Rng, depth: usize, in_case: bool) -> Message {
+        const NAMES: [&str; 4] = ["name", "count", "user.name", "first-name"];
+        const TEMPLATES: [&str; 6] = [
+            "{v, number}",
+            "{p, number, currency/EUR}",
+            "{x, number, ::.00 group-off}",
+            "{d, date, long}",
+            "{t, time, short}",
+            "{items, list, or}",
+        ];
+
+        let mut elements: Vec<MessageElement> = Vec::new();
+        for _ in 0..rng.below(5) {
+            let element = match rng.below(if depth == 0 { 4 } else { 6 }) {
+                0 | 1 => MessageElement::Text(random_text(rng)),
+                2 if in_case => MessageElement::PluralHash,
+                2 => MessageElement::Parameter(rng.pick(&NAMES).to_string()),
+                3 => crate::parser::parse_message_complete(rng.pick::<&str>(&TEMPLATES)).unwrap().elements.remove(0),
+                4 => {
+                    let keywords = [PluralSelector::Zero, PluralSelector::One, PluralSelector::Few, PluralSelector::Many];
+                    let mut cases = Vec::new();
+                    for (i, selector) in keywords.into_iter().enumerate() {
+                        let selector = match rng.below(3) {
+                            0 => continue,
+                            1 => selector,
+                            _ => PluralSelector::Exact(i as i64 - 1),
+                        };
+                        cases.push(PluralCase { selector, message: random_message(rng, depth - 1, true) });
+                    }
+                    cases.push(PluralCase { selector: PluralSelector::Range(10, 20), message: random_message(rng, depth - 1, true) });
+                    cases.push(PluralCase { selector: PluralSelector::Other, message: random_message(rng, depth - 1, true) });
+                    let hash_skeleton = (rng.below(3) == 0).then(|| rng.pick(&["compact-short", "percent .0"]).to_string());
+                    MessageElement::Plural(PluralExpression { parameter: rng.pick(&NAMES).to_string(), cases, hash_skeleton })
+                }
+                _ => {
+                    let mut cases = Vec::new();
+                    for selector in ["male", "a, b", "it's", "en-US", "x.y"] {
+                        if rng.below(2) == 0 {
+                            cases.push(SelectCase { selector: selector.to_string(), message: random_message(rng, depth - 1, true) });
+                        }
+                    }
+                    cases.push(SelectCase { selector: "other".to_string(), message: random_message(rng, depth - 1, true) });
+                    MessageElement::Select(SelectExpression { parameter: rng.pick(&NAMES).to_string(), cases })
+                }
+            };
+
+            match (elements.last_mut(), element) {
+                (Some(MessageElement::Text(previous)), MessageElement::Text(text)) => previous.push_str(&text),
+                (_, element) => elements.push(element),
+            }
+        }
+        Message::new(elements)
+    }
+
+    fn random_text(rng: &mut Rng) -> String {
+        const CHARS: [char; 12] = ['a', 'b', ' ', '{', '}', '#', '\'', '\'', ',', '=', '\n', 'é'];
+        (0..1 + rng.below(6)).map(|_| *rng.pick(&CHARS)).collect()
+    }
+
+    #[test]
+    fn test_display_round_trips_random_messages() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..500 {
+            let message = random_message(&mut rng, 3, false);
+            let serialized = message.to_icu_string();
+            let reparsed = crate::parser::parse_message_complete(&serialized);
+            assert_eq!(reparsed.as_ref(), Ok(&message), "round trip changed the message: {serialized}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_plural_selector_shape() {
+        assert_eq!(serde_json::to_string(&PluralSelector::Exact(3)).unwrap(), r#"{"Exact":3}"#);
+        assert_eq!(serde_json::to_string(&PluralSelector::One).unwrap(), r#""One""#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_message_round_trip() {
+        let (_, message) = crate::parser::parse_message(
+            "{count, plural, =0{none} one{# item} other{{price, number, currency/EUR} each}} {g, select, other{{d, date, short}}}",
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&message).unwrap();
+        let restored: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, message);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parameter_source_hash_maps() {
+        let mut strings = HashMap::new();
+        strings.insert("name".to_string(), "Alice".to_string());
+        assert_eq!(ParameterSource::get(&strings, "name"), Some(ParameterValue::String("Alice")));
+        assert_eq!(ParameterSource::get(&strings, "unknown"), None);
+
+        let mut values = HashMap::new();
+        values.insert("count".to_string(), ParameterValue::Number(3));
+        assert_eq!(ParameterSource::get(&values, "count"), Some(ParameterValue::Number(3)));
+    }
+
+    #[test]
+    fn test_parameter_value_from() {
+        let owned = "Bob".to_string();
+        let decimal: FixedDecimal = "1.50".parse().unwrap();
+        let items = ["a", "b"];
+
+        assert_eq!(ParameterValue::from("Ann"), ParameterValue::String("Ann"));
+        assert_eq!(ParameterValue::from(&owned), ParameterValue::String("Bob"));
+        assert_eq!(ParameterValue::from(7_i32), ParameterValue::Number(7));
+        assert_eq!(ParameterValue::from(-7_i64), ParameterValue::Number(-7));
+        assert_eq!(ParameterValue::from(255_u8), ParameterValue::Number(255));
+        assert_eq!(ParameterValue::from(42_usize), ParameterValue::Number(42));
+        assert_eq!(ParameterValue::from(u64::MAX), ParameterValue::BigInteger(u64::MAX as i128));
+        assert_eq!(ParameterValue::from(2.5), ParameterValue::Float(2.5));
+        assert_eq!(ParameterValue::from(true), ParameterValue::Bool(true));
+        assert_eq!(ParameterValue::from(&decimal), ParameterValue::Decimal(&decimal));
+        assert_eq!(ParameterValue::from(&items[..]).type_name(), "list");
+        assert_eq!(ParameterValue::from(Some(3)), ParameterValue::Number(3));
+        assert_eq!(ParameterValue::from(None::<&str>), ParameterValue::Null);
+        assert_eq!(None::<String>.as_parameter_value(), ParameterValue::Null);
+        assert_eq!(OwnedParameterValue::from(ParameterValue::Null), OwnedParameterValue::Null);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parameter_value_into_hash_map() {
+        let values: HashMap<String, ParameterValue> =
+            [("name".to_string(), "Ann".into()), ("count".to_string(), 3.into())].into_iter().collect();
+        let message = crate::parser::parse_message_complete("{name} has {count, plural, one{# file} other{# files}}").unwrap();
+        let formatted = crate::formatter::format_message(&message, &values, &icu::locid::locale!("en")).unwrap();
+        assert_eq!(formatted, "Ann has 3 files");
+    }
+
+    #[test]
+    fn test_parameter_names_nested() {
+        let message = crate::parser::parse_message_complete(
+            "{name} has {count, plural, one{# file in {folder}} other{# files in {folder}, {size, number} bytes}} \
+             {g, select, female{{d, date, short}} other{{t, time}}}",
+        )
+        .unwrap();
+
+        let names: Vec<String> = message.parameter_names().into_iter().collect();
+        assert_eq!(names, ["count", "d", "folder", "g", "name", "size", "t"]);
+
+        let message = crate::parser::parse_message_complete("{price, number, currency/$code}").unwrap();
+        assert_eq!(message.parameter_names().into_iter().collect::<Vec<_>>(), ["code", "price"]);
+    }
+
+    #[test]
+    fn test_selectors() {
+        let message = crate::parser::parse_message_complete(
+            "{n, plural, =0{none} one{# file} 2..4{# files} other{# files}} {g, select, male{He} female{She} other{They}}",
+        )
+        .unwrap();
+        let MessageElement::Plural(plural_expr) = &message.elements[0] else { unreachable!() };
+        let MessageElement::Select(select_expr) = &message.elements[2] else { unreachable!() };
+        assert_eq!(
+            plural_expr.selectors(),
+            [PluralSelector::Exact(0), PluralSelector::One, PluralSelector::Range(2, 4), PluralSelector::Other]
+        );
+        assert_eq!(select_expr.selectors(), ["male", "female", "other"]);
+
+        // Polish needs `few` and `many`, which the template doesn't declare
+        let declared = plural_expr.selectors();
+        let missing: Vec<_> = crate::formatter::plural_categories_for(&icu::locid::locale!("pl"))
+            .unwrap()
+            .into_iter()
+            .filter(|category| !declared.contains(category))
+            .collect();
+        assert_eq!(missing, [PluralSelector::Few, PluralSelector::Many]);
+    }
+
+    #[test]
+    fn test_element_kind_and_argument_name() {
+        let message = crate::parser::parse_message_complete(
+            "Hi {name}, {n, plural, other{# new}} {g, select, other{x}} {total, number} {d, date} {t, time} {items, list}",
+        )
+        .unwrap();
+        let arguments: Vec<_> = message
+            .elements
+            .iter()
+            .filter_map(|element| Some((element.kind(), element.argument_name()?)))
+            .collect();
+        assert_eq!(arguments, [
+            (ElementKind::Parameter, "name"),
+            (ElementKind::Plural, "n"),
+            (ElementKind::Select, "g"),
+            (ElementKind::Number, "total"),
+            (ElementKind::Date, "d"),
+            (ElementKind::Time, "t"),
+            (ElementKind::List, "items"),
+        ]);
+
+        assert_eq!(message.elements[0].kind(), ElementKind::Text);
+        assert_eq!(message.elements[0].argument_name(), None);
+        assert!(message.elements[3].is_plural() && !message.elements[3].is_select());
+        assert!(message.elements[5].is_select());
+        let MessageElement::Plural(plural_expr) = &message.elements[3] else { unreachable!() };
+        let hash = &plural_expr.cases[0].message.elements[0];
+        assert_eq!((hash.kind(), hash.argument_name()), (ElementKind::PluralHash, None));
+    }
+
+    #[test]
+    fn test_literal_text() {
+        let message = crate::parser::parse_message_complete(
+            "Hi {name}, you have {count, plural, =0{no files} one{# file in {folder}} other{# files}} left.",
+        )
+        .unwrap();
+        assert_eq!(message.literal_text(), "Hi , you have no files\n file in \n files left.");
+
+        let message = crate::parser::parse_message_complete("{g, select, female{She ''s} other{They}}  {n, number}").unwrap();
+        assert_eq!(message.literal_text(), "She 's\nThey  ");
+    }
+
+    #[test]
+    fn test_parameter_names_text_only() {
+        let message = crate::parser::parse_message_complete("No parameters # here").unwrap();
+        assert!(message.parameter_names().is_empty());
+    }
+
+    #[test]
+    fn test_integer_parameter_values() {
+        assert_eq!(7u8.as_parameter_value(), ParameterValue::Number(7));
+        assert_eq!((-7i16).as_parameter_value(), ParameterValue::Number(-7));
+        assert_eq!(7usize.as_parameter_value(), ParameterValue::Number(7));
+        assert_eq!(u64::MAX.as_parameter_value(), ParameterValue::BigInteger(u64::MAX as i128));
+        assert_eq!(i128::MIN.as_parameter_value(), ParameterValue::BigInteger(i128::MIN));
+    }
+
+    #[test]
+    fn test_parameters_builder() {
+        let names = vec!["Ann".to_string(), "Bo".to_string()];
+        let parameters = ParametersBuilder::new()
+            .insert("name", format!("User {}", 7))
+            .insert("count", 3usize)
+            .insert("names", names)
+            .insert("count", 4)
+            .build();
+
+        assert_eq!(ParameterSource::get(&parameters, "name"), Some(ParameterValue::String("User 7")));
+        assert_eq!(ParameterSource::get(&parameters, "count"), Some(ParameterValue::Number(4)));
+        match ParameterSource::get(&parameters, "names") {
+            Some(ParameterValue::List(items)) => assert_eq!(items.iter().collect::<Vec<_>>(), ["Ann", "Bo"]),
+            other => panic!("expected list, got {other:?}"),
+        }
+        assert_eq!(ParameterSource::get(&parameters, "missing"), None);
+    }
 }
@@ -0,0 +1,211 @@
+#[cfg(feature = "iso4217")]
+use crate::types::NumberFormatType;
+use crate::types::{Message, MessageElement, PluralSelector};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    MissingOther,
+    DuplicateSelector(String),
+    EmptyExpression,
+    // Only reported with the `iso4217` feature, which embeds the code list
+    UnknownCurrency(String),
+}
+
+impl fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationErrorKind::MissingOther => write!(f, "missing 'other' case"),
+            ValidationErrorKind::DuplicateSelector(selector) => write!(f, "duplicate selector '{selector}'"),
+            ValidationErrorKind::EmptyExpression => write!(f, "no cases"),
+            ValidationErrorKind::UnknownCurrency(code) => write!(f, "unknown currency code '{code}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    // Name of the argument the problem was found in
+    pub parameter: String,
+    pub kind: ValidationErrorKind,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "argument '{}': {}", self.parameter, self.kind)
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+impl Message {
+    // Checks the structural rules the parser does not enforce: every plural and select
+    // needs an `other` case, and selectors must be unique within one expression. With
+    // the `iso4217` feature, currency codes must also be ISO 4217 codes.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        validate_message(self, &mut errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+fn validate_message(message: &Message, errors: &mut Vec<ValidationError>) {
+    for element in &message.elements {
+        match element {
+            MessageElement::Plural(plural_expr) => {
+                let selectors: Vec<String> = plural_expr.cases.iter().map(|case| case.selector.to_string()).collect();
+                let has_other = plural_expr.cases.iter().any(|case| case.selector == PluralSelector::Other);
+                validate_cases(&plural_expr.parameter, &selectors, has_other, errors);
+                for case in &plural_expr.cases {
+                    validate_message(&case.message, errors);
+                }
+            }
+            MessageElement::Select(select_expr) => {
+                let selectors: Vec<String> = select_expr.cases.iter().map(|case| case.selector.clone()).collect();
+                let has_other = selectors.iter().any(|selector| selector == "other");
+                validate_cases(&select_expr.parameter, &selectors, has_other, errors);
+                for case in &select_expr.cases {
+                    validate_message(&case.message, errors);
+                }
+            }
+            MessageElement::Shared(message) => validate_message(message, errors),
+            #[cfg(feature = "iso4217")]
+            MessageElement::Number(number_expr) => {
+                if let Some(code) = currency_code(&number_expr.format_type)
+                    && !is_iso4217(code)
+                {
+                    errors.push(ValidationError {
+                        parameter: number_expr.parameter.clone(),
+                        kind: ValidationErrorKind::UnknownCurrency(code.to_string()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The literal currency code of a number format; codes read from a parameter are
+// only known at format time
+#[cfg(feature = "iso4217")]
+fn currency_code(format_type: &NumberFormatType) -> Option<&str> {
+    let code = match format_type {
+        NumberFormatType::Currency(code, _) => code.as_deref()?,
+        NumberFormatType::Skeleton(skeleton) => skeleton.split_whitespace().find_map(|token| token.strip_prefix("currency/"))?,
+        _ => return None,
+    };
+    (!code.starts_with('$')).then_some(code)
+}
+
+#[cfg(feature = "iso4217")]
+fn is_iso4217(code: &str) -> bool {
+    ISO_4217.binary_search(&code.to_ascii_uppercase().as_str()).is_ok()
+}
+
+// Active ISO 4217 codes, including funds and precious metals, sorted
+#[cfg(feature = "iso4217")]
+const ISO_4217: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT", "BGN", "BHD", "BIF",
+    "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD", "CDF", "CHE", "CHF", "CHW", "CLF",
+    "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR",
+    "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT",
+    "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP",
+    "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS",
+    "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV", "WST", "XAF", "XAG", "XAU",
+    "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER",
+    "ZAR", "ZMW", "ZWG",
+];
+
+fn validate_cases(parameter: &str, selectors: &[String], has_other: bool, errors: &mut Vec<ValidationError>) {
+    let error = |kind| ValidationError { parameter: parameter.to_string(), kind };
+
+    if selectors.is_empty() {
+        errors.push(error(ValidationErrorKind::EmptyExpression));
+        return;
+    }
+    if !has_other {
+        errors.push(error(ValidationErrorKind::MissingOther));
+    }
+    for (i, selector) in selectors.iter().enumerate() {
+        // Report each duplicated selector once, at its second occurrence
+        if selectors[..i].iter().filter(|earlier| *earlier == selector).count() == 1 {
+            errors.push(error(ValidationErrorKind::DuplicateSelector(selector.clone())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_message_complete;
+    use crate::types::SelectExpression;
+
+    fn validate(template: &str) -> Result<(), Vec<ValidationError>> {
+        parse_message_complete(template).unwrap().validate()
+    }
+
+    #[test]
+    fn test_validate_ok() {
+        assert_eq!(validate("{count, plural, one{# item} other{# items}}"), Ok(()));
+        assert_eq!(validate("Hello {name}"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_missing_other() {
+        let errors = validate("{count, plural, one{# item}} {g, select, male{he}}").unwrap_err();
+        assert_eq!(errors, vec![
+            ValidationError { parameter: "count".to_string(), kind: ValidationErrorKind::MissingOther },
+            ValidationError { parameter: "g".to_string(), kind: ValidationErrorKind::MissingOther },
+        ]);
+        assert_eq!(errors[0].to_string(), "argument 'count': missing 'other' case");
+    }
+
+    #[test]
+    fn test_validate_duplicate_selector() {
+        let errors = validate("{count, plural, =1{a} =1{b} =1{c} other{d}}").unwrap_err();
+        assert_eq!(errors, vec![ValidationError {
+            parameter: "count".to_string(),
+            kind: ValidationErrorKind::DuplicateSelector("=1".to_string()),
+        }]);
+    }
+
+    #[test]
+    fn test_validate_nested() {
+        let errors = validate("{g, select, other{{count, plural, one{#}}}}").unwrap_err();
+        assert_eq!(errors, vec![ValidationError { parameter: "count".to_string(), kind: ValidationErrorKind::MissingOther }]);
+    }
+
+    #[test]
+    fn test_validate_empty_expression() {
+        let message = Message::new(vec![MessageElement::Select(SelectExpression {
+            parameter: "g".to_string(),
+            cases: vec![],
+        })]);
+        let errors = message.validate().unwrap_err();
+        assert_eq!(errors[0].to_string(), "argument 'g': no cases");
+    }
+
+    #[cfg(feature = "iso4217")]
+    #[test]
+    fn test_validate_currency_codes() {
+        assert!(ISO_4217.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(validate("{p, number, currency/EUR} {q, number, ::currency/jpy .00} {r, number, currency}"), Ok(()));
+        assert_eq!(validate("{p, number, currency/$code}"), Ok(()));
+
+        let errors = validate("{p, number, currency/ZZZ} {g, select, other{{q, number, ::currency/EURO}}}").unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                ValidationError { parameter: "p".to_string(), kind: ValidationErrorKind::UnknownCurrency("ZZZ".to_string()) },
+                ValidationError { parameter: "q".to_string(), kind: ValidationErrorKind::UnknownCurrency("EURO".to_string()) },
+            ]
+        );
+        assert_eq!(errors[0].to_string(), "argument 'p': unknown currency code 'ZZZ'");
+    }
+}
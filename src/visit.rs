@@ -0,0 +1,257 @@
+use crate::types::{
+    DateExpression, ListExpression, Message, MessageElement, NumberExpression, PluralCase, PluralExpression, SelectCase,
+    SelectExpression, TimeExpression,
+};
+use alloc::string::String;
+use alloc::sync::Arc;
+
+// Walks a message read-only. Every method defaults to doing nothing, except the
+// plural and select methods which walk into their cases, and `visit_shared` which
+// walks the shared message; override them without walking to skip nested messages.
+pub trait MessageVisitor {
+    fn visit_text(&mut self, _text: &str) {}
+
+    fn visit_parameter(&mut self, _name: &str) {}
+
+    fn visit_plural(&mut self, plural_expr: &PluralExpression) {
+        walk_plural(self, plural_expr);
+    }
+
+    fn visit_select(&mut self, select_expr: &SelectExpression) {
+        walk_select(self, select_expr);
+    }
+
+    fn visit_number(&mut self, _number_expr: &NumberExpression) {}
+
+    fn visit_date(&mut self, _date_expr: &DateExpression) {}
+
+    fn visit_time(&mut self, _time_expr: &TimeExpression) {}
+
+    fn visit_list(&mut self, _list_expr: &ListExpression) {}
+
+    fn visit_plural_hash(&mut self) {}
+
+    fn visit_shared(&mut self, message: &Message) {
+        message.accept(self);
+    }
+}
+
+pub fn walk_plural<V: MessageVisitor + ?Sized>(visitor: &mut V, plural_expr: &PluralExpression) {
+    for case in &plural_expr.cases {
+        case.message.accept(visitor);
+    }
+}
+
+pub fn walk_select<V: MessageVisitor + ?Sized>(visitor: &mut V, select_expr: &SelectExpression) {
+    for case in &select_expr.cases {
+        case.message.accept(visitor);
+    }
+}
+
+// Rebuilds a message by value. Each method returns the element that replaces the
+// one it was given, so a fold can change an element's kind; the defaults keep the
+// element as it is, folding the cases of plurals and selects first.
+pub trait MessageFolder {
+    fn fold_text(&mut self, text: String) -> MessageElement {
+        MessageElement::Text(text)
+    }
+
+    fn fold_parameter(&mut self, name: String) -> MessageElement {
+        MessageElement::Parameter(name)
+    }
+
+    fn fold_plural(&mut self, plural_expr: PluralExpression) -> MessageElement {
+        MessageElement::Plural(fold_plural_cases(self, plural_expr))
+    }
+
+    fn fold_select(&mut self, select_expr: SelectExpression) -> MessageElement {
+        MessageElement::Select(fold_select_cases(self, select_expr))
+    }
+
+    fn fold_number(&mut self, number_expr: NumberExpression) -> MessageElement {
+        MessageElement::Number(number_expr)
+    }
+
+    fn fold_date(&mut self, date_expr: DateExpression) -> MessageElement {
+        MessageElement::Date(date_expr)
+    }
+
+    fn fold_time(&mut self, time_expr: TimeExpression) -> MessageElement {
+        MessageElement::Time(time_expr)
+    }
+
+    fn fold_list(&mut self, list_expr: ListExpression) -> MessageElement {
+        MessageElement::List(list_expr)
+    }
+
+    fn fold_plural_hash(&mut self) -> MessageElement {
+        MessageElement::PluralHash
+    }
+
+    // Folds a copy of the shared message, unless this is its only holder. Messages
+    // that shared it each get their own copy, so intern the result again to share.
+    fn fold_shared(&mut self, message: Arc<Message>) -> MessageElement {
+        MessageElement::Shared(Arc::new(Arc::unwrap_or_clone(message).fold(self)))
+    }
+}
+
+pub fn fold_plural_cases<F: MessageFolder + ?Sized>(folder: &mut F, plural_expr: PluralExpression) -> PluralExpression {
+    let cases = plural_expr
+        .cases
+        .into_iter()
+        .map(|case| PluralCase { selector: case.selector, message: case.message.fold(folder) })
+        .collect();
+    PluralExpression { parameter: plural_expr.parameter, cases, hash_skeleton: plural_expr.hash_skeleton }
+}
+
+pub fn fold_select_cases<F: MessageFolder + ?Sized>(folder: &mut F, select_expr: SelectExpression) -> SelectExpression {
+    let cases = select_expr
+        .cases
+        .into_iter()
+        .map(|case| SelectCase { selector: case.selector, message: case.message.fold(folder) })
+        .collect();
+    SelectExpression { parameter: select_expr.parameter, cases }
+}
+
+impl Message {
+    pub fn accept<V: MessageVisitor + ?Sized>(&self, visitor: &mut V) {
+        for element in &self.elements {
+            match element {
+                MessageElement::Text(text) => visitor.visit_text(text),
+                MessageElement::Parameter(name) => visitor.visit_parameter(name),
+                MessageElement::Plural(plural_expr) => visitor.visit_plural(plural_expr),
+                MessageElement::Select(select_expr) => visitor.visit_select(select_expr),
+                MessageElement::Number(number_expr) => visitor.visit_number(number_expr),
+                MessageElement::Date(date_expr) => visitor.visit_date(date_expr),
+                MessageElement::Time(time_expr) => visitor.visit_time(time_expr),
+                MessageElement::List(list_expr) => visitor.visit_list(list_expr),
+                MessageElement::PluralHash => visitor.visit_plural_hash(),
+                MessageElement::Shared(message) => visitor.visit_shared(message),
+            }
+        }
+    }
+
+    pub fn fold<F: MessageFolder + ?Sized>(self, folder: &mut F) -> Message {
+        let elements = self
+            .elements
+            .into_iter()
+            .map(|element| match element {
+                MessageElement::Text(text) => folder.fold_text(text),
+                MessageElement::Parameter(name) => folder.fold_parameter(name),
+                MessageElement::Plural(plural_expr) => folder.fold_plural(plural_expr),
+                MessageElement::Select(select_expr) => folder.fold_select(select_expr),
+                MessageElement::Number(number_expr) => folder.fold_number(number_expr),
+                MessageElement::Date(date_expr) => folder.fold_date(date_expr),
+                MessageElement::Time(time_expr) => folder.fold_time(time_expr),
+                MessageElement::List(list_expr) => folder.fold_list(list_expr),
+                MessageElement::PluralHash => folder.fold_plural_hash(),
+                MessageElement::Shared(message) => folder.fold_shared(message),
+            })
+            .collect();
+        Message { elements }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_message_complete;
+
+    #[derive(Default)]
+    struct Placeholders {
+        names: Vec<String>,
+        hashes: usize,
+    }
+
+    impl MessageVisitor for Placeholders {
+        fn visit_parameter(&mut self, name: &str) {
+            self.names.push(name.to_string());
+        }
+
+        fn visit_number(&mut self, number_expr: &NumberExpression) {
+            self.names.push(number_expr.parameter.clone());
+        }
+
+        fn visit_plural_hash(&mut self) {
+            self.hashes += 1;
+        }
+    }
+
+    #[test]
+    fn test_visitor_walks_nested_cases() {
+        let message = parse_message_complete("{a} {n, plural, one{# {b}} other{# {c, number}}} {g, select, other{{d}}}").unwrap();
+        let mut placeholders = Placeholders::default();
+        message.accept(&mut placeholders);
+        assert_eq!(placeholders.names, ["a", "b", "c", "d"]);
+        assert_eq!(placeholders.hashes, 2);
+    }
+
+    #[test]
+    fn test_visitor_can_skip_cases() {
+        struct TopLevel(Vec<String>);
+
+        impl MessageVisitor for TopLevel {
+            fn visit_parameter(&mut self, name: &str) {
+                self.0.push(name.to_string());
+            }
+
+            fn visit_select(&mut self, select_expr: &SelectExpression) {
+                self.0.push(select_expr.parameter.clone());
+            }
+        }
+
+        let message = parse_message_complete("{a} {g, select, other{{b}}}").unwrap();
+        let mut top_level = TopLevel(Vec::new());
+        message.accept(&mut top_level);
+        assert_eq!(top_level.0, ["a", "g"]);
+    }
+
+    #[test]
+    fn test_folder_trims_text() {
+        struct TrimText;
+
+        impl MessageFolder for TrimText {
+            fn fold_text(&mut self, text: String) -> MessageElement {
+                MessageElement::Text(text.trim().to_string())
+            }
+        }
+
+        let message = parse_message_complete("  Hi {name}  {g, select, male{ he } other{ they }}").unwrap();
+        let folded = message.fold(&mut TrimText);
+        assert_eq!(folded.to_string(), "Hi{name}{g, select, male{he} other{they}}");
+    }
+
+    #[test]
+    fn test_folder_replaces_element_kind() {
+        struct Redact;
+
+        impl MessageFolder for Redact {
+            fn fold_parameter(&mut self, name: String) -> MessageElement {
+                MessageElement::Text(format!("<{name}>"))
+            }
+        }
+
+        let message = parse_message_complete("{n, plural, other{{who} has #}}").unwrap();
+        assert_eq!(message.fold(&mut Redact).to_string(), "{n, plural, other{<who> has #}}");
+    }
+
+    #[test]
+    fn test_visitor_and_folder_walk_shared_cases() {
+        let mut message = parse_message_complete("{a} {g, select, male{{b}} other{{b}}}").unwrap();
+        crate::intern::MessageInterner::new().intern(&mut message);
+
+        let mut placeholders = Placeholders::default();
+        message.accept(&mut placeholders);
+        assert_eq!(placeholders.names, ["a", "b", "b"]);
+
+        struct Upper;
+
+        impl MessageFolder for Upper {
+            fn fold_parameter(&mut self, name: String) -> MessageElement {
+                MessageElement::Parameter(name.to_uppercase())
+            }
+        }
+
+        assert_eq!(message.fold(&mut Upper).to_string(), "{A} {g, select, male{{B}} other{{B}}}");
+    }
+}